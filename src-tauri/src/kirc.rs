@@ -1,8 +1,19 @@
+mod cap;
+mod casemap;
+mod client;
 pub(crate) mod commands;
+mod connection;
 mod core;
 mod ctcp;
 mod emits;
+mod isupport;
 pub(crate) mod manager;
+mod modes;
 pub(crate) mod persistence;
+mod proxy;
+mod sasl;
+mod services;
 pub(crate) mod state;
+mod throttle;
+mod tls;
 mod types;
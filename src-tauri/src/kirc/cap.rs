@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+
+/// 별도 설정이 없을 때 요청하는 capability 목록
+///
+/// 처리하지 않는 capability를 요청하면 메세지만 늘어나므로 처리하는 것만 요청
+pub(in crate::kirc) const DEFAULT_CAPABILITIES: &[&str] = &[
+    "server-time",
+    "echo-message",
+    "extended-join",
+    "multi-prefix",
+    "cap-notify",
+];
+
+/// CAP REQ 한 줄에 담을 capability 목록의 최대 길이
+const CAP_REQ_LINE_LIMIT: usize = 400;
+
+/// 서버별 IRCv3 capability 협상 상태, 연결마다 초기화
+#[derive(Default, Clone, Debug)]
+pub(in crate::kirc) struct CapState {
+    /// 서버가 LS/NEW로 알려준 capability와 값
+    available: HashMap<String, Option<String>>,
+    /// 서버가 ACK한 capability
+    enabled: HashSet<String>,
+    /// REQ를 보내고 ACK/NAK를 기다리는 capability
+    pending: HashSet<String>,
+    /// 등록 전 협상 중인지 (CAP END 전송 전)
+    negotiating: bool,
+}
+
+impl CapState {
+    /// 새 연결의 등록 시작
+    pub(in crate::kirc) fn start_negotiation(&mut self) {
+        *self = Self {
+            negotiating: true,
+            ..Self::default()
+        };
+    }
+
+    pub(in crate::kirc) fn is_negotiating(&self) -> bool {
+        self.negotiating
+    }
+
+    /// CAP END 전송 후 호출
+    pub(in crate::kirc) fn finish_negotiation(&mut self) {
+        self.negotiating = false;
+    }
+
+    pub(in crate::kirc) fn is_available(&self, name: &str) -> bool {
+        self.available.contains_key(name)
+    }
+
+    pub(in crate::kirc) fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(name)
+    }
+
+    pub(in crate::kirc) fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// 활성화된 capability 목록 (정렬됨)
+    pub(in crate::kirc) fn enabled(&self) -> Vec<String> {
+        let mut enabled: Vec<String> = self.enabled.iter().cloned().collect();
+        enabled.sort();
+        enabled
+    }
+
+    /// LS/NEW 응답으로 받은 목록 추가
+    pub(in crate::kirc) fn add_available(&mut self, list: &str) {
+        for (name, value) in parse_cap_list(list) {
+            self.available
+                .insert(name.to_string(), value.map(str::to_string));
+        }
+    }
+
+    /// DEL 응답으로 받은 목록 제거, 실제로 비활성화된 capability 반환
+    pub(in crate::kirc) fn remove_available(&mut self, list: &str) -> Vec<String> {
+        parse_cap_list(list)
+            .filter_map(|(name, _)| {
+                self.available.remove(name);
+                self.pending.remove(name);
+                self.enabled.take(name)
+            })
+            .collect()
+    }
+
+    /// 원하는 capability 중 서버가 제공하고 아직 요청하지 않은 것을 pending으로 표시 후 반환
+    pub(in crate::kirc) fn request<S: AsRef<str>>(&mut self, wanted: &[S]) -> Vec<String> {
+        let mut requested = Vec::new();
+        for name in wanted.iter().map(AsRef::as_ref) {
+            if self.available.contains_key(name)
+                && !self.enabled.contains(name)
+                && self.pending.insert(name.to_string())
+            {
+                requested.push(name.to_string());
+            }
+        }
+        requested
+    }
+
+    /// ACK 처리, `-` 접두사는 비활성화
+    pub(in crate::kirc) fn acknowledge(&mut self, list: &str) {
+        for (name, _) in parse_cap_list(list) {
+            if let Some(disabled) = name.strip_prefix('-') {
+                self.pending.remove(disabled);
+                self.enabled.remove(disabled);
+            } else {
+                self.pending.remove(name);
+                self.enabled.insert(name.to_string());
+            }
+        }
+    }
+
+    /// NAK 처리
+    pub(in crate::kirc) fn reject(&mut self, list: &str) {
+        for (name, _) in parse_cap_list(list) {
+            self.pending.remove(name.trim_start_matches('-'));
+        }
+    }
+}
+
+/// capability 목록을 CAP REQ 줄 단위로 분할
+pub(super) fn req_lines(caps: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for cap in caps {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + cap.len() <= CAP_REQ_LINE_LIMIT => {
+                line.push(' ');
+                line.push_str(cap);
+            }
+            _ => lines.push(cap.clone()),
+        }
+    }
+    lines
+}
+
+/// CAP 응답의 파라미터에서 (다음 줄이 더 있는지, capability 목록)을 추출
+///
+/// `CAP * LS :a b` 는 세 번째 필드에, `CAP * LS * :a b` 는 네 번째 필드에 목록이 담겨 옴
+pub(super) fn cap_params<'a>(field: Option<&'a str>, param: Option<&'a str>) -> (bool, &'a str) {
+    match (field, param) {
+        (Some(marker), Some(list)) => (marker == "*", list),
+        (Some(list), None) => (false, list),
+        (None, Some(list)) => (false, list),
+        (None, None) => (false, ""),
+    }
+}
+
+/// `sasl=PLAIN,EXTERNAL` 형태의 토큰을 (이름, 값)으로 분리
+pub(super) fn parse_cap_list(list: &str) -> impl Iterator<Item = (&str, Option<&str>)> {
+    list.split_whitespace()
+        .map(|token| match token.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (token, None),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_params_single_line() {
+        assert_eq!(
+            cap_params(Some("multi-prefix sasl"), None),
+            (false, "multi-prefix sasl")
+        );
+    }
+
+    #[test]
+    fn test_cap_params_multi_line() {
+        assert_eq!(
+            cap_params(Some("*"), Some("multi-prefix sasl")),
+            (true, "multi-prefix sasl")
+        );
+        assert_eq!(cap_params(Some("x"), Some("batch")), (false, "batch"));
+    }
+
+    #[test]
+    fn test_cap_state_request_and_ack() {
+        let mut caps = CapState::default();
+        caps.start_negotiation();
+        caps.add_available("multi-prefix sasl=PLAIN server-time");
+
+        let requested = caps.request(&["server-time", "echo-message", "multi-prefix"]);
+        assert_eq!(requested, vec!["server-time", "multi-prefix"]);
+        assert!(caps.is_pending());
+
+        // 이미 pending인 capability는 다시 요청하지 않음
+        assert!(caps.request(&["server-time"]).is_empty());
+
+        caps.acknowledge("server-time multi-prefix");
+        assert!(!caps.is_pending());
+        assert_eq!(caps.enabled(), vec!["multi-prefix", "server-time"]);
+        assert!(caps.is_negotiating());
+    }
+
+    #[test]
+    fn test_cap_state_nak_and_disable() {
+        let mut caps = CapState::default();
+        caps.add_available("batch chghost");
+        caps.request(&["batch", "chghost"]);
+
+        caps.reject("batch");
+        caps.acknowledge("chghost");
+        assert!(!caps.is_pending());
+        assert_eq!(caps.enabled(), vec!["chghost"]);
+
+        caps.acknowledge("-chghost");
+        assert!(caps.enabled().is_empty());
+    }
+
+    #[test]
+    fn test_cap_state_new_and_del() {
+        let mut caps = CapState::default();
+        caps.add_available("away-notify");
+        caps.request(&["away-notify"]);
+        caps.acknowledge("away-notify");
+
+        caps.add_available("setname");
+        assert!(caps.is_available("setname"));
+
+        assert_eq!(
+            caps.remove_available("away-notify setname"),
+            vec!["away-notify"]
+        );
+        assert!(!caps.is_enabled("away-notify"));
+        assert!(!caps.is_available("setname"));
+    }
+
+    #[test]
+    fn test_req_lines() {
+        let caps: Vec<String> = vec!["a".repeat(250), "b".repeat(100), "c".repeat(100)];
+        let lines = req_lines(&caps);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 351);
+        assert_eq!(lines[1], "c".repeat(100));
+    }
+
+    #[test]
+    fn test_parse_cap_list() {
+        let caps: Vec<_> = parse_cap_list("multi-prefix sasl=PLAIN,EXTERNAL  batch").collect();
+        assert_eq!(
+            caps,
+            vec![
+                ("multi-prefix", None),
+                ("sasl", Some("PLAIN,EXTERNAL")),
+                ("batch", None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+/// ISUPPORT CASEMAPPING, 채널/닉네임의 대소문자 비교 규칙
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub(in crate::kirc) enum CaseMapping {
+    /// A-Z만 접음
+    Ascii,
+    /// ascii + `[]\~` → `{}|^` (토큰이 없을 때 기본값)
+    #[default]
+    Rfc1459,
+    /// ascii + `[]\` → `{}|`
+    StrictRfc1459,
+}
+
+impl CaseMapping {
+    /// 알 수 없는 값(rfc7613 등)은 최소한 ascii 비교는 되도록 처리
+    pub(in crate::kirc) fn from_token(token: &str) -> Self {
+        match token.to_ascii_lowercase().as_str() {
+            "rfc1459" => CaseMapping::Rfc1459,
+            "strict-rfc1459" => CaseMapping::StrictRfc1459,
+            _ => CaseMapping::Ascii,
+        }
+    }
+
+    pub(in crate::kirc) fn fold(self, name: &str) -> String {
+        name.chars().map(|c| self.fold_char(c)).collect()
+    }
+
+    pub(in crate::kirc) fn eq(self, a: &str, b: &str) -> bool {
+        a.len() == b.len()
+            && a.chars()
+                .map(|c| self.fold_char(c))
+                .eq(b.chars().map(|c| self.fold_char(c)))
+    }
+
+    fn fold_char(self, c: char) -> char {
+        match (self, c) {
+            (_, 'A'..='Z') => c.to_ascii_lowercase(),
+            (CaseMapping::Rfc1459 | CaseMapping::StrictRfc1459, '[') => '{',
+            (CaseMapping::Rfc1459 | CaseMapping::StrictRfc1459, ']') => '}',
+            (CaseMapping::Rfc1459 | CaseMapping::StrictRfc1459, '\\') => '|',
+            (CaseMapping::Rfc1459, '~') => '^',
+            _ => c,
+        }
+    }
+}
+
+/// CASEMAPPING으로 접은 채널/닉네임 비교 키, 표시용 이름은 따로 보관
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(in crate::kirc) struct IrcKey(String);
+
+impl IrcKey {
+    pub(in crate::kirc) fn new(mapping: CaseMapping, name: &str) -> Self {
+        Self(mapping.fold(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_token() {
+        assert_eq!(CaseMapping::from_token("rfc1459"), CaseMapping::Rfc1459);
+        assert_eq!(
+            CaseMapping::from_token("strict-rfc1459"),
+            CaseMapping::StrictRfc1459
+        );
+        assert_eq!(CaseMapping::from_token("ascii"), CaseMapping::Ascii);
+        assert_eq!(CaseMapping::from_token("rfc7613"), CaseMapping::Ascii);
+    }
+
+    #[test]
+    fn test_fold_ascii() {
+        assert_eq!(CaseMapping::Ascii.fold("#Rust[]\\~"), "#rust[]\\~");
+        assert!(CaseMapping::Ascii.eq("#Rust", "#rust"));
+        assert!(!CaseMapping::Ascii.eq("nick[a]", "nick{a}"));
+    }
+
+    #[test]
+    fn test_fold_rfc1459() {
+        assert_eq!(CaseMapping::Rfc1459.fold("Nick[A]\\~"), "nick{a}|^");
+        assert!(CaseMapping::Rfc1459.eq("Nick[a]", "nick{A}"));
+        assert!(CaseMapping::Rfc1459.eq("a~", "A^"));
+    }
+
+    #[test]
+    fn test_fold_strict_rfc1459() {
+        assert_eq!(CaseMapping::StrictRfc1459.fold("Nick[A]\\~"), "nick{a}|~");
+        assert!(!CaseMapping::StrictRfc1459.eq("a~", "a^"));
+    }
+
+    #[test]
+    fn test_key_equality() {
+        assert_eq!(
+            IrcKey::new(CaseMapping::Rfc1459, "#Rust"),
+            IrcKey::new(CaseMapping::Rfc1459, "#rust")
+        );
+        assert_ne!(
+            IrcKey::new(CaseMapping::Ascii, "#a[b]"),
+            IrcKey::new(CaseMapping::Ascii, "#a{b}")
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use crate::kirc::throttle::TokenBucket;
+use crate::kirc::types::server::SendRate;
+use anyhow::anyhow;
+use futures::{Sink, SinkExt};
+use irc::proto::{CapSubCommand, Command, Message, NegotiationVersion};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// 서버로 보낼 메세지를 writer task에 넘기는 송신 핸들
+///
+/// 핸들이 drop되면 writer는 대기열을 버리고 남은 긴급 메세지(QUIT 등)만 보낸 뒤 종료
+pub(super) struct IrcClient {
+    tx: UnboundedSender<Outgoing>,
+}
+
+/// writer task로 넘기는 메세지, echo면 실제로 보낸 뒤 actor에 돌려줘 UI에 표시
+struct Outgoing {
+    message: Message,
+    echo: bool,
+}
+
+/// 속도 제한으로 아직 보내지 못한 메세지, 줄 수는 UI에 표시
+///
+/// 서버 상태에 하나만 두고 연결마다 다시 사용하므로, 끝나지 않은 이전 연결의 writer가
+/// 새 연결의 메세지를 가져가거나 지우지 못하게 연결 번호(generation)로 소유자를 구분
+pub(in crate::kirc) struct SendQueue {
+    pending: Mutex<Pending>,
+    depth: watch::Sender<usize>,
+}
+
+#[derive(Default)]
+struct Pending {
+    generation: u64,
+    lines: VecDeque<Outgoing>,
+}
+
+impl SendQueue {
+    pub(in crate::kirc) fn new() -> Self {
+        Self {
+            pending: Mutex::new(Pending::default()),
+            depth: watch::Sender::new(0),
+        }
+    }
+
+    pub(in crate::kirc) fn depth(&self) -> usize {
+        self.pending.lock().unwrap().lines.len()
+    }
+
+    pub(in crate::kirc) fn subscribe(&self) -> watch::Receiver<usize> {
+        self.depth.subscribe()
+    }
+
+    /// 보내지 않은 메세지를 모두 버리고 버린 줄 수 반환
+    pub(in crate::kirc) fn clear(&self) -> usize {
+        let mut pending = self.pending.lock().unwrap();
+        let cleared = pending.lines.len();
+        pending.lines.clear();
+        self.depth.send_replace(0);
+        cleared
+    }
+
+    /// 새 연결이 대기열을 넘겨받음, 이전 연결에서 남은 메세지는 버림
+    fn start(&self) -> u64 {
+        let mut pending = self.pending.lock().unwrap();
+        pending.generation += 1;
+        pending.lines.clear();
+        self.depth.send_replace(0);
+        pending.generation
+    }
+
+    /// 대기열을 소유한 연결이면 비우고 버린 줄 수 반환
+    fn clear_owned(&self, generation: u64) -> usize {
+        if self.pending.lock().unwrap().generation != generation {
+            return 0;
+        }
+        self.clear()
+    }
+
+    fn push(&self, generation: u64, message: Outgoing) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.generation == generation {
+            pending.lines.push_back(message);
+            self.depth.send_replace(pending.lines.len());
+        }
+    }
+
+    fn pop(&self, generation: u64) -> Option<Outgoing> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.generation != generation {
+            return None;
+        }
+        let message = pending.lines.pop_front();
+        self.depth.send_replace(pending.lines.len());
+        message
+    }
+
+    /// 다른 연결이 넘겨받았으면 보낼 메세지가 없는 것으로 처리
+    fn is_empty(&self, generation: u64) -> bool {
+        let pending = self.pending.lock().unwrap();
+        pending.generation != generation || pending.lines.is_empty()
+    }
+}
+
+impl IrcClient {
+    /// 이전 연결에서 남은 대기열은 비우고 시작
+    ///
+    /// 함께 반환하는 receiver로 `send_echoed`로 보낸 메세지를 실제로 보낸 뒤 받음
+    pub(super) fn new<S>(
+        sink: S,
+        queue: Arc<SendQueue>,
+        rate: SendRate,
+    ) -> (Self, JoinHandle<()>, UnboundedReceiver<Message>)
+    where
+        S: Sink<Message> + Unpin + Send + 'static,
+        S::Error: Display + Send,
+    {
+        let generation = queue.start();
+        let (tx, rx) = unbounded_channel();
+        let (echo_tx, echo_rx) = unbounded_channel();
+        let writer = tokio::spawn(write_loop(sink, rx, echo_tx, queue, generation, rate));
+
+        (Self { tx }, writer, echo_rx)
+    }
+
+    pub(super) fn send<M: Into<Message>>(&self, message: M) -> anyhow::Result<()> {
+        self.send_outgoing(message.into(), false)
+    }
+
+    /// 대기열에서 취소되지 않고 실제로 보낸 경우에만 화면에 표시할 메세지
+    pub(super) fn send_echoed<M: Into<Message>>(&self, message: M) -> anyhow::Result<()> {
+        self.send_outgoing(message.into(), true)
+    }
+
+    fn send_outgoing(&self, message: Message, echo: bool) -> anyhow::Result<()> {
+        self.tx
+            .send(Outgoing { message, echo })
+            .map_err(|e| anyhow!("Failed to send: {}", e))
+    }
+
+    pub(super) fn send_cap_ls(&self, version: NegotiationVersion) -> anyhow::Result<()> {
+        let version = match version {
+            NegotiationVersion::V301 => None,
+            NegotiationVersion::V302 => Some("302".to_string()),
+        };
+        self.send(Command::CAP(None, CapSubCommand::LS, version, None))
+    }
+
+    pub(super) fn send_cap_req(&self, list: String) -> anyhow::Result<()> {
+        self.send(Command::CAP(None, CapSubCommand::REQ, None, Some(list)))
+    }
+
+    pub(super) fn send_cap_end(&self) -> anyhow::Result<()> {
+        self.send(Command::CAP(None, CapSubCommand::END, None, None))
+    }
+
+    pub(super) fn send_sasl(&self, data: impl ToString) -> anyhow::Result<()> {
+        self.send(Command::AUTHENTICATE(data.to_string()))
+    }
+
+    pub(super) fn send_join(&self, channel: &str) -> anyhow::Result<()> {
+        self.send(Command::JOIN(channel.to_string(), None, None))
+    }
+
+    pub(super) fn send_part(&self, channel: &str) -> anyhow::Result<()> {
+        self.send(Command::PART(channel.to_string(), None))
+    }
+
+    pub(super) fn send_privmsg(&self, target: &str, message: &str) -> anyhow::Result<()> {
+        self.send(Command::PRIVMSG(target.to_string(), message.to_string()))
+    }
+
+    pub(super) fn send_notice(&self, target: &str, message: &str) -> anyhow::Result<()> {
+        self.send(Command::NOTICE(target.to_string(), message.to_string()))
+    }
+
+    pub(super) fn send_quit(&self, message: &str) -> anyhow::Result<()> {
+        self.send(Command::QUIT(Some(message.to_string())))
+    }
+}
+
+/// 등록, 연결 유지, 종료 메세지는 대기열과 속도 제한 없이 바로 보냄
+fn is_urgent(message: &Message) -> bool {
+    matches!(
+        message.command,
+        Command::PASS(_)
+            | Command::NICK(_)
+            | Command::USER(..)
+            | Command::CAP(..)
+            | Command::AUTHENTICATE(_)
+            | Command::PING(..)
+            | Command::PONG(..)
+            | Command::QUIT(_)
+    )
+}
+
+async fn write_loop<S>(
+    mut sink: S,
+    mut rx: UnboundedReceiver<Outgoing>,
+    echo_tx: UnboundedSender<Message>,
+    queue: Arc<SendQueue>,
+    generation: u64,
+    rate: SendRate,
+) where
+    S: Sink<Message> + Unpin,
+    S::Error: Display,
+{
+    let mut bucket = TokenBucket::new(rate.burst(), rate.interval(), Instant::now());
+
+    loop {
+        let wait = bucket.until_next(Instant::now());
+
+        let outgoing = tokio::select! {
+            biased;
+            received = rx.recv() => {
+                match received {
+                    Some(outgoing) if is_urgent(&outgoing.message) => outgoing,
+                    Some(outgoing) => {
+                        queue.push(generation, outgoing);
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            _ = tokio::time::sleep(wait), if !queue.is_empty(generation) => {
+                if !bucket.try_take(Instant::now()) {
+                    continue;
+                }
+                // 기다리는 동안 취소되었을 수 있음
+                let Some(outgoing) = queue.pop(generation) else {
+                    continue;
+                };
+                outgoing
+            }
+        };
+
+        let echo = outgoing.echo.then(|| outgoing.message.clone());
+        if let Err(e) = sink.send(outgoing.message).await {
+            error!(event = "irc_write_error", error = %e, "Failed to write IRC message");
+            break;
+        }
+        if let Some(message) = echo {
+            let _ = echo_tx.send(message);
+        }
+    }
+
+    let dropped = queue.clear_owned(generation);
+    if dropped > 0 {
+        info!(
+            event = "send_queue_dropped",
+            count = dropped,
+            "Dropped queued messages on close"
+        );
+    }
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    fn privmsg(text: &str) -> Command {
+        Command::PRIVMSG("#kirc".to_string(), text.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_previous_writer_leaves_new_queue_alone() {
+        let queue = Arc::new(SendQueue::new());
+
+        let (old_sink, mut old_sent) = mpsc::unbounded::<Message>();
+        let (old_client, old_writer, _) =
+            IrcClient::new(old_sink, queue.clone(), SendRate::default());
+
+        // 기본 속도 제한은 5줄까지 바로 보내고 나머지는 대기열에 남음
+        let (new_sink, mut new_sent) = mpsc::unbounded::<Message>();
+        let (new_client, _new_writer, _) =
+            IrcClient::new(new_sink, queue.clone(), SendRate::default());
+        for i in 0..7 {
+            new_client.send(privmsg(&i.to_string())).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(queue.depth(), 2);
+
+        // 이전 연결의 writer는 새 연결의 메세지를 보내거나 지우지 않음
+        drop(old_client);
+        old_writer.await.unwrap();
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(old_sent.next().await, None);
+
+        for i in 0..5 {
+            let message = new_sent.next().await.unwrap();
+            assert_eq!(message.command, privmsg(&i.to_string()));
+        }
+    }
+}
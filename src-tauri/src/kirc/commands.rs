@@ -1,9 +1,14 @@
 use crate::error::MyCustomError;
 use crate::kirc::commands::payload::{
-    ChangeNickPayload, ChannelInfo, ChannelPayload, ConnectServerPayload, ServerInfo,
+    ChangeNickPayload, ChannelInfo, ChannelPayload, ConnectServerPayload, ProxyInfo, ServerInfo,
+    UpdateServerPayload,
 };
+use crate::kirc::emits::{emit_query_changed, QueryChange};
+use crate::kirc::isupport::ServerFeatures;
 use crate::kirc::manager::KircManager;
+use crate::kirc::state::channel::ChannelUser;
 use crate::kirc::state::kirc::KircState;
+use crate::kirc::types::server::{CtcpConfig, SendRate};
 use crate::kirc::types::{ServerCommand, ServerId};
 use anyhow::Context;
 use std::sync::Arc;
@@ -28,18 +33,39 @@ pub(crate) fn get_servers(
         let channel_infos = server_state
             .channels()
             .into_iter()
-            .map(|(name, s)| ChannelInfo::new(&name, s.locked))
+            .map(|(name, s)| {
+                ChannelInfo::new(
+                    &name,
+                    s.locked,
+                    s.rejoin_after_kick,
+                    s.mode_string(),
+                    s.topic().cloned(),
+                )
+            })
             .collect();
 
         let server_info = ServerInfo::builder()
             .id(id)
-            .name(config.server())
+            .name(&server_state.display_name())
             .host(config.server())
             .port(config.port())
             .tls(config.use_tls())
             .nickname(config.nickname())
+            .alt_nicks(config.alt_nicks())
+            .account(config.sasl().and_then(|sasl| sasl.account()))
+            .cert_fingerprint(config.cert_fingerprint())
             .status(server_state.status())
+            .capabilities(server_state.enabled_caps())
+            .proxy(config.proxy().map(ProxyInfo::new))
+            .auto_connect(config.auto_connect())
+            .endpoints(config.endpoints())
+            .connected_endpoint(server_state.connected_endpoint())
+            .endpoint_failures(server_state.endpoint_failures())
+            .user_modes(server_state.user_modes())
             .channels(channel_infos)
+            .queries(server_state.queries())
+            .send_rate(config.send_rate())
+            .pending_messages(server_state.send_queue().depth())
             .build();
 
         infos.push(server_info);
@@ -74,6 +100,36 @@ pub(crate) fn join_channel(
     Ok(())
 }
 
+/// 받은 INVITE 수락
+#[tauri::command]
+pub(crate) fn accept_invite(
+    server_id: ServerId,
+    channel: String,
+    manager: State<KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: accept invite invoked, server_id: {server_id}, channel: {channel}");
+    manager
+        .join_channel(server_id, &channel)
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn send_invite(
+    server_id: ServerId,
+    nickname: String,
+    channel: String,
+    state: State<'_, Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: send invite invoked, server_id: {server_id}, nickname: {nickname}, channel: {channel}");
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    server.send_command(ServerCommand::Invite { nickname, channel })?;
+
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) fn leave_channel(
     payload: ChannelPayload,
@@ -108,6 +164,101 @@ pub(crate) fn send_message(
     Ok(())
 }
 
+/// `/me`, send_message와 같은 잠금 정책
+#[tauri::command]
+pub(crate) fn send_action(
+    server_id: ServerId,
+    target: String,
+    message: String,
+    state: State<Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: send action invoked, server_id: {server_id}, target: {target}, message: {message}");
+
+    if state.is_channel_locked(server_id, &target) {
+        return Err(MyCustomError::Anyhow(anyhow::anyhow!("Channel is locked")));
+    }
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    server.send_command(ServerCommand::Action { target, message })?;
+
+    Ok(())
+}
+
+/// PING에 인자가 없으면 보낸 시각(ms)을 담아 응답에서 왕복 시간 계산
+#[tauri::command]
+pub(crate) fn send_ctcp(
+    server_id: ServerId,
+    target: String,
+    command: String,
+    params: Option<String>,
+    state: State<Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: send ctcp invoked, server_id: {server_id}, target: {target}, command: {command}");
+
+    if state.is_channel_locked(server_id, &target) {
+        return Err(MyCustomError::Anyhow(anyhow::anyhow!("Channel is locked")));
+    }
+
+    let command = command.to_uppercase();
+    let params = match params.filter(|params| !params.is_empty()) {
+        Some(params) => params,
+        None if command == "PING" => chrono::Utc::now().timestamp_millis().to_string(),
+        None => String::new(),
+    };
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    server.send_command(ServerCommand::Ctcp {
+        target,
+        command,
+        params,
+    })?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn send_notice(
+    server_id: ServerId,
+    target: String,
+    message: String,
+    state: State<Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: send notice invoked, server_id: {server_id}, target: {target}");
+
+    if state.is_channel_locked(server_id, &target) {
+        return Err(MyCustomError::Anyhow(anyhow::anyhow!("Channel is locked")));
+    }
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    server.send_command(ServerCommand::Notice { target, message })?;
+
+    Ok(())
+}
+
+/// 빈 문자열이면 토픽 삭제
+#[tauri::command]
+pub(crate) fn set_topic(
+    server_id: ServerId,
+    channel: String,
+    topic: String,
+    state: State<'_, Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: set topic invoked, server_id: {server_id}, channel: {channel}");
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    if let Some(topiclen) = server.isupport().topiclen() {
+        if topic.len() > topiclen {
+            return Err(MyCustomError::Anyhow(anyhow::anyhow!(
+                "Topic is too long (max {topiclen} bytes)"
+            )));
+        }
+    }
+
+    server.send_command(ServerCommand::Topic { channel, topic })?;
+
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) fn cancel_connect(
     server_id: ServerId,
@@ -174,6 +325,21 @@ pub(crate) fn unlock_channel(
     Ok(())
 }
 
+/// 강퇴 후 자동 재입장 설정, delay_secs가 None이면 끔
+#[tauri::command]
+pub(crate) fn set_channel_rejoin_after_kick(
+    payload: ChannelPayload,
+    delay_secs: Option<u64>,
+    state: State<'_, Arc<KircState>>,
+) -> Result<(), MyCustomError> {
+    let server = state
+        .get_server(payload.server_id())
+        .context("Can't find server")?;
+    server.set_channel_rejoin_after_kick(payload.channel(), delay_secs);
+
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) fn is_channel_locked(
     payload: ChannelPayload,
@@ -182,6 +348,65 @@ pub(crate) fn is_channel_locked(
     Ok(state.is_channel_locked(payload.server_id(), payload.channel()))
 }
 
+#[tauri::command]
+pub(crate) fn get_channel_users(
+    payload: ChannelPayload,
+    state: State<'_, Arc<KircState>>,
+) -> Result<Vec<ChannelUser>, MyCustomError> {
+    let server = state
+        .get_server(payload.server_id())
+        .context("Can't find server")?;
+
+    Ok(server
+        .channel_users(payload.channel())
+        .context("Can't find channel")?)
+}
+
+/// 개인 대화를 열고 표시용 닉네임 반환 (이미 열려 있으면 기존 이름)
+#[tauri::command]
+pub(crate) fn open_query(
+    server_id: ServerId,
+    nickname: String,
+    state: State<'_, Arc<KircState>>,
+    app_handle: AppHandle,
+) -> Result<String, MyCustomError> {
+    info!("Tauri command: open query invoked, server_id: {server_id}, nickname: {nickname}");
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    if server.isupport().channel_target(&nickname).is_some() {
+        return Err(MyCustomError::Anyhow(anyhow::anyhow!(
+            "{nickname} is a channel"
+        )));
+    }
+
+    let (nick, opened) = server.open_query(&nickname);
+    if opened {
+        emit_query_changed(
+            &app_handle,
+            server_id,
+            QueryChange::Opened { nick: nick.clone() },
+        )?;
+    }
+
+    Ok(nick)
+}
+
+#[tauri::command]
+pub(crate) fn close_query(
+    server_id: ServerId,
+    nickname: String,
+    state: State<'_, Arc<KircState>>,
+    app_handle: AppHandle,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: close query invoked, server_id: {server_id}, nickname: {nickname}");
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    let nick = server.close_query(&nickname).context("Can't find query")?;
+    emit_query_changed(&app_handle, server_id, QueryChange::Closed { nick })?;
+
+    Ok(())
+}
+
 #[tauri::command]
 #[instrument(skip(state), fields(server_id = %payload.server_id))]
 pub(crate) fn change_nickname(
@@ -200,9 +425,145 @@ pub(crate) fn change_nickname(
     Ok(())
 }
 
+#[tauri::command]
+pub(crate) async fn remove_server(
+    server_id: ServerId,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: remove server invoked, server_id: {server_id}");
+
+    manager
+        .remove_server(server_id)
+        .await
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn update_server_config(
+    payload: UpdateServerPayload,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!(
+        "Tauri command: update server config invoked, server_id: {}",
+        payload.server_id()
+    );
+
+    manager
+        .update_server_config(
+            payload.server_id(),
+            |config| payload.apply(config),
+            payload.reconnect(),
+        )
+        .await
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+/// 속도 제한으로 아직 보내지 않은 메세지를 모두 취소하고 취소한 줄 수 반환
+#[tauri::command]
+pub(crate) fn cancel_pending_messages(
+    server_id: ServerId,
+    state: State<'_, Arc<KircState>>,
+) -> Result<usize, MyCustomError> {
+    info!("Tauri command: cancel pending messages invoked, server_id: {server_id}");
+
+    let server = state.get_server(server_id).context("Can't find server")?;
+    Ok(server.send_queue().clear())
+}
+
+/// 다음 연결부터 적용
+#[tauri::command]
+pub(crate) async fn set_send_rate(
+    server_id: ServerId,
+    send_rate: SendRate,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: set send rate invoked, server_id: {server_id}");
+
+    manager
+        .update_server_config(server_id, |c| c.with_send_rate(send_rate), false)
+        .await
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_ctcp_config(
+    server_id: ServerId,
+    state: State<'_, Arc<KircState>>,
+) -> Result<CtcpConfig, MyCustomError> {
+    let server = state.get_server(server_id).context("Can't find server")?;
+
+    Ok(server.config().ctcp().clone())
+}
+
+/// 다음 요청부터 바로 적용되므로 다시 연결하지 않음
+#[tauri::command]
+pub(crate) async fn set_ctcp_config(
+    server_id: ServerId,
+    config: CtcpConfig,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: set ctcp config invoked, server_id: {server_id}");
+
+    manager
+        .update_server_config(server_id, |c| c.with_ctcp(config), false)
+        .await
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_server_features(
+    server_id: ServerId,
+    state: State<'_, Arc<KircState>>,
+) -> Result<ServerFeatures, MyCustomError> {
+    let server = state.get_server(server_id).context("Can't find server")?;
+
+    Ok(ServerFeatures::from(&server.isupport()))
+}
+
+#[tauri::command]
+pub(crate) fn accept_tls_certificate(
+    server_id: ServerId,
+    fingerprint: String,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: accept tls certificate invoked, server_id: {server_id}");
+
+    manager
+        .accept_tls_certificate(server_id, &fingerprint)
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn reject_tls_certificate(
+    server_id: ServerId,
+    manager: State<'_, KircManager>,
+) -> Result<(), MyCustomError> {
+    info!("Tauri command: reject tls certificate invoked, server_id: {server_id}");
+
+    manager
+        .reject_tls_certificate(server_id)
+        .map_err(MyCustomError::Anyhow)?;
+
+    Ok(())
+}
+
 mod payload {
-    use crate::kirc::types::server::ServerConfig;
-    use crate::kirc::types::{ChannelId, ServerId, ServerStatus};
+    use crate::kirc::state::channel::Topic;
+    use crate::kirc::types::server::{
+        AutoConnect, ClientCertificate, Endpoint, PerformCommand, ProxyConfig, ProxyKind,
+        SaslConfig, SendRate, ServerConfig, ServicesConfig, TlsOptions,
+    };
+    use crate::kirc::types::{ChannelId, EndpointFailure, ServerId, ServerStatus};
     use serde::{Deserialize, Serialize};
 
     #[derive(Deserialize, Debug, Clone)]
@@ -212,6 +573,28 @@ mod payload {
         port: u16,
         tls: bool,
         nickname: String,
+        #[serde(default)]
+        sasl: Option<SaslConfig>,
+        #[serde(default)]
+        client_cert: Option<ClientCertificate>,
+        #[serde(default)]
+        capabilities: Option<Vec<String>>,
+        #[serde(default)]
+        alt_nicks: Vec<String>,
+        #[serde(default)]
+        services: Option<ServicesConfig>,
+        #[serde(default)]
+        password: Option<String>,
+        #[serde(default)]
+        perform: Vec<PerformCommand>,
+        #[serde(default)]
+        proxy: Option<ProxyConfig>,
+        #[serde(default)]
+        tls_options: TlsOptions,
+        #[serde(default)]
+        auto_connect: AutoConnect,
+        #[serde(default)]
+        fallback_endpoints: Vec<Endpoint>,
     }
 
     impl ConnectServerPayload {
@@ -220,12 +603,70 @@ mod payload {
         }
 
         pub(super) fn to_config(&self) -> ServerConfig {
-            ServerConfig::new(
+            let config = ServerConfig::new(
                 self.host.to_string(),
                 self.port,
                 self.tls,
                 self.nickname.to_string(),
             )
+            .with_sasl(self.sasl.clone())
+            .with_client_cert(self.client_cert.clone())
+            .with_alt_nicks(self.alt_nicks.clone())
+            .with_services(self.services.clone())
+            .with_password(self.password.clone())
+            .with_perform(self.perform.clone())
+            .with_proxy(self.proxy.clone())
+            .with_tls_options(self.tls_options.clone())
+            .with_auto_connect(self.auto_connect)
+            .with_fallback_endpoints(self.fallback_endpoints.clone());
+
+            match &self.capabilities {
+                Some(capabilities) => config.with_capabilities(capabilities.clone()),
+                None => config,
+            }
+        }
+    }
+
+    /// 값이 있는 항목만 기존 설정에 덮어씀
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(crate) struct UpdateServerPayload {
+        server_id: ServerId,
+        host: Option<String>,
+        port: Option<u16>,
+        tls: Option<bool>,
+        nickname: Option<String>,
+        auto_connect: Option<AutoConnect>,
+        #[serde(default)]
+        reconnect: bool,
+    }
+
+    impl UpdateServerPayload {
+        pub(super) fn server_id(&self) -> ServerId {
+            self.server_id
+        }
+
+        pub(super) fn reconnect(&self) -> bool {
+            self.reconnect
+        }
+
+        pub(super) fn apply(&self, mut config: ServerConfig) -> ServerConfig {
+            if let Some(host) = &self.host {
+                config = config.with_server(host.clone());
+            }
+            if let Some(port) = self.port {
+                config = config.with_port(port);
+            }
+            if let Some(tls) = self.tls {
+                config = config.with_use_tls(tls);
+            }
+            if let Some(nickname) = &self.nickname {
+                config = config.with_nickname(nickname.clone());
+            }
+            if let Some(auto_connect) = self.auto_connect {
+                config = config.with_auto_connect(auto_connect);
+            }
+            config
         }
     }
 
@@ -251,13 +692,25 @@ mod payload {
     pub(crate) struct ChannelInfo {
         name: String,
         locked: bool,
+        rejoin_after_kick: Option<u64>,
+        modes: String,
+        topic: Option<Topic>,
     }
 
     impl ChannelInfo {
-        pub(super) fn new(name: &str, locked: bool) -> Self {
+        pub(super) fn new(
+            name: &str,
+            locked: bool,
+            rejoin_after_kick: Option<u64>,
+            modes: String,
+            topic: Option<Topic>,
+        ) -> Self {
             Self {
                 name: name.to_string(),
                 locked,
+                rejoin_after_kick,
+                modes,
+                topic,
             }
         }
     }
@@ -271,8 +724,44 @@ mod payload {
         port: u16,
         tls: bool,
         nickname: String,
+        alt_nicks: Vec<String>,
+        account: Option<String>,
+        cert_fingerprint: Option<String>,
         status: ServerStatus,
+        capabilities: Vec<String>,
+        proxy: Option<ProxyInfo>,
+        auto_connect: AutoConnect,
+        endpoints: Vec<Endpoint>,
+        connected_endpoint: Option<Endpoint>,
+        endpoint_failures: Vec<EndpointFailure>,
+        user_modes: String,
         channels: Vec<ChannelInfo>,
+        queries: Vec<String>,
+        send_rate: SendRate,
+        /// 속도 제한으로 대기 중인 메세지 줄 수
+        pending_messages: usize,
+    }
+
+    /// 프록시 정보 (비밀번호 제외)
+    #[derive(Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(crate) struct ProxyInfo {
+        #[serde(rename = "type")]
+        kind: ProxyKind,
+        host: String,
+        port: u16,
+        username: Option<String>,
+    }
+
+    impl ProxyInfo {
+        pub(super) fn new(proxy: &ProxyConfig) -> Self {
+            Self {
+                kind: proxy.kind(),
+                host: proxy.host().to_string(),
+                port: proxy.port(),
+                username: proxy.username().map(str::to_string),
+            }
+        }
     }
 
     impl ServerInfo {
@@ -289,8 +778,21 @@ mod payload {
         port: Option<u16>,
         tls: Option<bool>,
         nickname: Option<String>,
+        alt_nicks: Vec<String>,
+        account: Option<String>,
+        cert_fingerprint: Option<String>,
         status: Option<ServerStatus>,
+        capabilities: Vec<String>,
+        proxy: Option<ProxyInfo>,
+        auto_connect: AutoConnect,
+        endpoints: Vec<Endpoint>,
+        connected_endpoint: Option<Endpoint>,
+        endpoint_failures: Vec<EndpointFailure>,
+        user_modes: String,
         channels: Option<Vec<ChannelInfo>>,
+        queries: Vec<String>,
+        send_rate: SendRate,
+        pending_messages: usize,
     }
 
     impl ServerInfoBuilder {
@@ -318,8 +820,21 @@ mod payload {
                 port: self.port.unwrap(),
                 tls: self.tls.unwrap(),
                 nickname: self.nickname.clone().unwrap(),
+                alt_nicks: self.alt_nicks.clone(),
+                account: self.account.clone(),
+                cert_fingerprint: self.cert_fingerprint.clone(),
                 status: self.status.clone().unwrap(),
+                capabilities: self.capabilities.clone(),
+                proxy: self.proxy.clone(),
+                auto_connect: self.auto_connect,
+                endpoints: self.endpoints.clone(),
+                connected_endpoint: self.connected_endpoint.clone(),
+                endpoint_failures: self.endpoint_failures.clone(),
+                user_modes: self.user_modes.clone(),
                 channels: self.channels.clone().unwrap(),
+                queries: self.queries.clone(),
+                send_rate: self.send_rate,
+                pending_messages: self.pending_messages,
             }
         }
 
@@ -353,15 +868,80 @@ mod payload {
             self
         }
 
+        pub(super) fn alt_nicks(&mut self, alt_nicks: &[String]) -> &mut Self {
+            self.alt_nicks = alt_nicks.to_vec();
+            self
+        }
+
+        pub(super) fn account(&mut self, account: Option<&str>) -> &mut Self {
+            self.account = account.map(str::to_string);
+            self
+        }
+
+        pub(super) fn cert_fingerprint(&mut self, cert_fingerprint: Option<&str>) -> &mut Self {
+            self.cert_fingerprint = cert_fingerprint.map(str::to_string);
+            self
+        }
+
         pub(super) fn status(&mut self, status: ServerStatus) -> &mut Self {
             self.status = Some(status);
             self
         }
 
+        pub(super) fn capabilities(&mut self, capabilities: Vec<String>) -> &mut Self {
+            self.capabilities = capabilities;
+            self
+        }
+
+        pub(super) fn proxy(&mut self, proxy: Option<ProxyInfo>) -> &mut Self {
+            self.proxy = proxy;
+            self
+        }
+
+        pub(super) fn auto_connect(&mut self, auto_connect: AutoConnect) -> &mut Self {
+            self.auto_connect = auto_connect;
+            self
+        }
+
+        pub(super) fn endpoints(&mut self, endpoints: Vec<Endpoint>) -> &mut Self {
+            self.endpoints = endpoints;
+            self
+        }
+
+        pub(super) fn connected_endpoint(&mut self, endpoint: Option<Endpoint>) -> &mut Self {
+            self.connected_endpoint = endpoint;
+            self
+        }
+
+        pub(super) fn endpoint_failures(&mut self, failures: Vec<EndpointFailure>) -> &mut Self {
+            self.endpoint_failures = failures;
+            self
+        }
+
+        pub(super) fn user_modes(&mut self, user_modes: String) -> &mut Self {
+            self.user_modes = user_modes;
+            self
+        }
+
         pub(super) fn channels(&mut self, channels: Vec<ChannelInfo>) -> &mut Self {
             self.channels = Some(channels);
             self
         }
+
+        pub(super) fn queries(&mut self, queries: Vec<String>) -> &mut Self {
+            self.queries = queries;
+            self
+        }
+
+        pub(super) fn send_rate(&mut self, send_rate: SendRate) -> &mut Self {
+            self.send_rate = send_rate;
+            self
+        }
+
+        pub(super) fn pending_messages(&mut self, pending_messages: usize) -> &mut Self {
+            self.pending_messages = pending_messages;
+            self
+        }
     }
 
     #[derive(Deserialize)]
@@ -0,0 +1,215 @@
+use crate::kirc::types::server::{Endpoint, ServerConfig};
+use crate::kirc::{proxy, tls};
+use anyhow::bail;
+use irc::proto::error::ProtocolError;
+use irc::proto::line::LineCodec;
+use irc::proto::{Command, IrcCodec, Message};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_util::bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+use tracing::{info, warn};
+
+pub(super) trait AsyncStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> AsyncStream for T {}
+
+pub(super) type Transport = Framed<Box<dyn AsyncStream>, KircCodec>;
+
+/// IrcCodec과 같지만 채널 MODE는 원본 인자를 유지한 `Command::Raw`로 디코딩
+///
+/// irc-proto는 RFC에 없는 모드(`+j 3:5` 등)의 인자를 알지 못해 남은 인자를 버리므로
+/// CHANMODES 기준으로 다시 파싱할 수 있게 원본 인자 그대로 넘김
+pub(super) struct KircCodec {
+    inner: LineCodec,
+}
+
+impl KircCodec {
+    pub(super) fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            inner: LineCodec::new("utf-8")?,
+        })
+    }
+}
+
+impl Decoder for KircCodec {
+    type Item = Message;
+    type Error = ProtocolError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, ProtocolError> {
+        let Some(line) = self.inner.decode(src)? else {
+            return Ok(None);
+        };
+
+        let mut message: Message = line.parse()?;
+        if let Command::ChannelMODE(..) = message.command {
+            message.command = Command::Raw("MODE".to_string(), raw_params(&line));
+        }
+        Ok(Some(message))
+    }
+}
+
+impl Encoder<Message> for KircCodec {
+    type Error = ProtocolError;
+
+    fn encode(&mut self, message: Message, dst: &mut BytesMut) -> Result<(), ProtocolError> {
+        self.inner
+            .encode(IrcCodec::sanitize(message.to_string()), dst)
+    }
+}
+
+/// 태그, prefix, 명령을 건너뛴 메세지 인자
+fn raw_params(line: &str) -> Vec<String> {
+    let mut rest = line.trim_end_matches(['\r', '\n']);
+    if rest.starts_with('@') {
+        rest = rest.split_once(' ').map_or("", |(_, rest)| rest);
+    }
+    rest = rest.trim_start_matches(' ');
+    if rest.starts_with(':') {
+        rest = rest.split_once(' ').map_or("", |(_, rest)| rest);
+    }
+    // 명령
+    rest = rest
+        .trim_start_matches(' ')
+        .split_once(' ')
+        .map_or("", |(_, rest)| rest);
+
+    let mut params = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(' ');
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(trailing) = rest.strip_prefix(':') {
+            params.push(trailing.to_string());
+            break;
+        }
+        let (param, next) = rest.split_once(' ').unwrap_or((rest, ""));
+        params.push(param.to_string());
+        rest = next;
+    }
+    params
+}
+
+/// 연결 결과
+pub(super) enum Connection {
+    Ready(Transport),
+    /// TOFU: 사용자가 서버 인증서 fingerprint를 승인해야 사용
+    Untrusted {
+        transport: Transport,
+        endpoint: Endpoint,
+        fingerprint: String,
+    },
+}
+
+/// 주소 하나에 TCP/TLS로 (프록시가 있으면 프록시를 거쳐) 연결하고 IRC 코덱으로 감싼 transport 반환
+///
+/// irc 크레이트의 Client는 연결을 직접 만들어 클라이언트 인증서 등을 제어할 수 없어 분리
+pub(super) async fn connect(
+    config: &ServerConfig,
+    endpoint: &Endpoint,
+) -> anyhow::Result<Connection> {
+    let tcp = connect_tcp(config, endpoint).await?;
+
+    let mut untrusted = None;
+    let stream: Box<dyn AsyncStream> = if endpoint.use_tls() {
+        info!(event = "tls_connect", server = %endpoint.server());
+        let options = config.tls_options();
+        let pinned = config
+            .pinned_fingerprint(endpoint)
+            .map(tls::normalize_fingerprint);
+
+        match tls::connector(config, false)?
+            .connect(endpoint.server(), tcp)
+            .await
+        {
+            Ok(stream) => {
+                // 검증을 통과해도 고정된 fingerprint가 있으면 추가로 확인
+                if let Some(pinned) = &pinned {
+                    let fingerprint = tls::peer_fingerprint(&stream)?;
+                    if &fingerprint != pinned {
+                        bail!("TLS certificate fingerprint mismatch: {fingerprint}");
+                    }
+                }
+                Box::new(stream)
+            }
+            Err(e) if pinned.is_some() || options.trust_on_first_use() => {
+                // 검증에 실패한 인증서는 고정된 인증서이거나 사용자가 승인해야 사용
+                warn!(event = "tls_verify_failed", error = %e, "Checking certificate fingerprint");
+                let tcp = connect_tcp(config, endpoint).await?;
+                let stream = tls::connector(config, true)?
+                    .connect(endpoint.server(), tcp)
+                    .await?;
+                let fingerprint = tls::peer_fingerprint(&stream)?;
+
+                if pinned.as_ref() != Some(&fingerprint) {
+                    if !options.trust_on_first_use() {
+                        bail!("TLS certificate fingerprint mismatch: {fingerprint}");
+                    }
+                    untrusted = Some(fingerprint);
+                }
+                Box::new(stream)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        Box::new(tcp)
+    };
+
+    let transport = Framed::new(stream, KircCodec::new()?);
+    Ok(match untrusted {
+        Some(fingerprint) => Connection::Untrusted {
+            transport,
+            endpoint: endpoint.clone(),
+            fingerprint,
+        },
+        None => Connection::Ready(transport),
+    })
+}
+
+async fn connect_tcp(config: &ServerConfig, endpoint: &Endpoint) -> anyhow::Result<TcpStream> {
+    Ok(match config.proxy() {
+        Some(proxy) => proxy::connect_via(proxy, endpoint.server(), endpoint.port()).await?,
+        None => TcpStream::connect((endpoint.server(), endpoint.port())).await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(line: &str) -> Message {
+        KircCodec::new()
+            .unwrap()
+            .decode(&mut BytesMut::from(line))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_decode_channel_mode_keeps_params() {
+        let message = decode("@time=2024-01-01T00:00:00Z :op!o@host MODE #c +jv 3:5 bob\r\n");
+        assert_eq!(message.source_nickname(), Some("op"));
+        assert_eq!(
+            message.command,
+            Command::Raw(
+                "MODE".to_string(),
+                vec![
+                    "#c".to_string(),
+                    "+jv".to_string(),
+                    "3:5".to_string(),
+                    "bob".to_string()
+                ]
+            )
+        );
+
+        // 유저 모드와 다른 명령은 그대로
+        let message = decode(":kirc MODE kirc :+iw\r\n");
+        assert!(matches!(message.command, Command::UserMODE(..)));
+        let message = decode(":a!a@host PRIVMSG #c :hello  world\r\n");
+        assert_eq!(
+            message.command,
+            Command::PRIVMSG("#c".to_string(), "hello  world".to_string())
+        );
+    }
+}
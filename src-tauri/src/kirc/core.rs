@@ -1,59 +1,106 @@
-use crate::kirc::ctcp::{parse_ctcp, CtcpCommand};
+use crate::kirc::cap::{cap_params, parse_cap_list, req_lines, CapState};
+use crate::kirc::client::IrcClient;
+use crate::kirc::connection::{connect, Connection};
+use crate::kirc::ctcp::{
+    ctcp_message, parse_ctcp, parse_ctcp_reply, ping_rtt, CtcpCommand, SOURCE_URL,
+    SUPPORTED_COMMANDS,
+};
 use crate::kirc::emits::{
-    emit_change_nick_failed, emit_server_status, emit_system_message, emit_ui_event,
+    emit_capabilities_changed, emit_change_nick_failed, emit_members_changed, emit_nick_recovered,
+    emit_nick_recovery_failed, emit_nickname_acquired, emit_query_changed, emit_send_queue,
+    emit_server_features, emit_server_status, emit_system_message, emit_tls_untrusted,
+    emit_ui_event, MembersChange, QueryChange,
+};
+use crate::kirc::isupport::ServerFeatures;
+use crate::kirc::modes::{parse_channel_modes, parse_user_modes, unparse_user_modes, ModeChange};
+use crate::kirc::sasl::{authenticate_chunks, plain_payload, SaslProgress};
+use crate::kirc::services::{
+    is_credential_command, parse_recovery_reply, recovery_message, NickRecovery, RecoveryReply,
+    GHOST_NICK_DELAY,
 };
+use crate::kirc::state::channel::{ChannelUser, Topic};
 use crate::kirc::state::kirc::KircState;
-use crate::kirc::types::server::ServerConfig;
-use crate::kirc::types::{ServerCommand, ServerId, ServerStatus};
+use crate::kirc::state::server::ServerState;
+use crate::kirc::throttle::{CtcpFlood, CtcpVerdict};
+use crate::kirc::types::server::{
+    CtcpConfig, Endpoint, NickRecoveryMethod, PerformCommand, SaslConfig, ServerConfig,
+};
+use crate::kirc::types::{ChannelId, EndpointFailure, ServerCommand, ServerId, ServerStatus};
+use anyhow::anyhow;
 use futures::prelude::*;
-use irc::client::prelude::*;
+use irc::proto::message::Tag;
+use irc::proto::{CapSubCommand, Command, Message, NegotiationVersion, Prefix, Response};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+use tokio::time::{timeout, Instant};
 use tracing::{debug, error, info, instrument, trace, warn};
 
+const KEEPALIVE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// 이 시간 동안 아무 메세지도 받지 못하면 PING 전송
+const PING_INTERVAL: Duration = Duration::from_secs(120);
+/// 이 시간 동안 아무 메세지도 받지 못하면 연결이 끊긴 것으로 판단
+const PING_TIMEOUT: Duration = Duration::from_secs(240);
+const WRITER_FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+/// 주소 하나에 연결을 기다리는 시간
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+/// TOFU 인증서 승인 대기 시간
+const TLS_APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// actor 종료 사유. 상태 전이는 manager의 supervisor가 결정
+pub(super) enum ActorExit {
+    /// 사용자가 요청한 QUIT
+    Quit,
+    /// 연결 또는 등록 시작 실패
+    Failed(String),
+    /// 연결된 뒤 예기치 않게 끊어짐
+    ConnectionLost(String),
+}
+
 #[instrument(name = "server_actor", skip_all, fields(server_id = %server_id))]
 pub(super) async fn server_actor(
     server_id: ServerId,
     server_config: ServerConfig,
+    start_endpoint: usize,
     app_handle: AppHandle,
-) {
+) -> ActorExit {
     // actor에선 error를 ?로 전파하지 않고, 소비/로깅만 하거나 이벤트로 전파
     debug!(server_id = %server_id, "Starting server actor");
 
-    let config = Config {
-        server: Some(server_config.server().to_string()),
-        port: Some(server_config.port()),
-        use_tls: Some(server_config.use_tls()),
-        nickname: Some(server_config.nickname().to_string()),
-        // alt 닉네임 직접 제어
-        /*alt_nicks: vec![
-            format!("{}_", server_config.nickname()),
-            format!("{}__", server_config.nickname()),
-        ],*/
-        alt_nicks: vec![],
-        ..Config::default()
-    };
+    let transport =
+        match connect_endpoints(server_id, &server_config, start_endpoint, &app_handle).await {
+            Ok(Connection::Ready(t)) => t,
+            Ok(Connection::Untrusted {
+                transport,
+                endpoint,
+                fingerprint,
+            }) => {
+                if !wait_for_tls_approval(server_id, endpoint, &fingerprint, &app_handle).await {
+                    return ActorExit::Failed("TLS certificate was not accepted".to_string());
+                }
+                transport
+            }
+            Err(e) => return ActorExit::Failed(e),
+        };
 
-    let mut client = match Client::from_config(config).await {
-        Ok(c) => c,
-        Err(e) => {
-            fail_state(server_id, app_handle, e.to_string());
-            return;
+    let send_queue = {
+        let state = app_handle.state::<Arc<KircState>>();
+        match state.get_server(server_id) {
+            Some(server) => server.send_queue(),
+            None => return ActorExit::Failed("Server not found".to_string()),
         }
     };
+    let mut queue_depth = send_queue.subscribe();
 
-    if let Err(e) = client.identify() {
-        fail_state(server_id, app_handle, e.to_string());
-        return;
-    }
+    let (sink, mut stream) = transport.split();
+    let (client, mut writer, mut echoed) =
+        IrcClient::new(sink, send_queue, server_config.send_rate());
 
-    let mut stream = match client.stream() {
-        Ok(s) => s,
-        Err(e) => {
-            fail_state(server_id, app_handle, e.to_string());
-            return;
-        }
-    };
+    if let Err(e) = register(&client, &server_config) {
+        return ActorExit::Failed(e.to_string());
+    }
 
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -66,33 +113,55 @@ pub(super) async fn server_actor(
 
     let _ = emit_server_status(&app_handle, server_id, ServerStatus::Registering);
 
+    // 서버 응답이 끊긴 연결 감지용
+    let mut keepalive = tokio::time::interval(KEEPALIVE_CHECK_INTERVAL);
+    let mut last_received = Instant::now();
+    let mut ping_sent = false;
+
     trace!(server_id = %server_id, "Start server actor loop");
-    loop {
+    let exit = loop {
         tokio::select! {
-            Some(result) = stream.next() => {
+            result = stream.next() => {
                 match result {
-                    Ok(message) => {
+                    Some(Ok(message)) => {
+                        last_received = Instant::now();
+                        ping_sent = false;
                         let _ = handle_message(&client, server_id, message, &app_handle);
                     }
-                    Err(irc::error::Error::NoUsableNick) => {
-                        // 사용 가능한 닉네임이 없을때 (단순 닉네임 중복 등)
-                        // 처음부터 사용가능한 닉네임이 없었다면 identify에서 걸렸을테니 break 하지 않고 continue
-                        debug!(event = "irc_stream_error", error = "NoUsableNick", "IRC stream error, No usable nick");
-
-                        // 닉네임 변경은 alt_nick 없이 실패시 emit 후 바로 continue
-                        let _ = emit_change_nick_failed(&app_handle, server_id, "Can't change nickname");
-
-                        continue
-                    }
-                    Err(e) => {
+                    Some(Err(e)) => {
                         error!(
                             event = "irc_stream_error",
                             error = ?e,
                             "IRC stream error, connection likely closed"
                         );
-                        break
+                        break ActorExit::ConnectionLost(e.to_string());
                     },
+                    None => {
+                        info!(event = "irc_stream_closed", "IRC connection closed by server");
+                        break ActorExit::ConnectionLost("Connection closed by server".to_string());
+                    }
+                }
+            }
+            _ = keepalive.tick() => {
+                let idle = last_received.elapsed();
+                if idle >= PING_TIMEOUT {
+                    error!(event = "ping_timeout", idle_secs = idle.as_secs(), "No response from server");
+                    break ActorExit::ConnectionLost("Ping timeout".to_string());
                 }
+
+                if idle >= PING_INTERVAL && !ping_sent {
+                    let _ = client.send(Command::PING(server_config.server().to_string(), None));
+                    ping_sent = true;
+                }
+
+                let _ = check_nick_recovery(server_id, &app_handle);
+            }
+            Ok(()) = queue_depth.changed() => {
+                let depth = *queue_depth.borrow_and_update();
+                let _ = emit_send_queue(&app_handle, server_id, depth);
+            }
+            Some(message) = echoed.recv() => {
+                echo_message(&client, server_id, &app_handle, message);
             }
             Some(cmd) = rx.recv() => {
                 match cmd {
@@ -103,27 +172,26 @@ pub(super) async fn server_actor(
                         }
                     }
                     ServerCommand::Privmsg { target, message } => {
-                        if let Err(e) = client.send_privmsg(&target, &message) {
+                        if let Err(e) = client.send_echoed(Command::PRIVMSG(target, message)) {
                             error!("Failed to send privmsg: {e}");
                         }
-
-                        let current_nick = {
-                            let state = app_handle.state::<Arc<KircState>>();
-                            if let Some(server) = state.get_server(server_id) {
-                                &server.current_nickname()
-                            } else {
-                                ""
-                            }
-                        };
-
-                        match Message::with_tags(None, Some(current_nick), "PRIVMSG", vec![&target, &message]) {
-                                Ok(msg) => {
-                                    handle_message(&client, server_id, msg, &app_handle).expect("Failed to handle message");
-                                }
-                                Err(_) => {
-                                    error!("Failed to create echo message");
-                                }
-                            }
+                    }
+                    ServerCommand::Action { target, message } => {
+                        let action = ctcp_message("ACTION", &message);
+                        if let Err(e) = client.send_echoed(Command::PRIVMSG(target, action)) {
+                            error!("Failed to send action: {e}");
+                        }
+                    }
+                    ServerCommand::Ctcp { target, command, params } => {
+                        info!(event = "ctcp_request", target = %target, command = %command);
+                        if let Err(e) = client.send_privmsg(&target, &ctcp_message(&command, &params)) {
+                            error!("Failed to send ctcp request: {e}");
+                        }
+                    }
+                    ServerCommand::Notice { target, message } => {
+                        if let Err(e) = client.send_echoed(Command::NOTICE(target, message)) {
+                            error!("Failed to send notice: {e}");
+                        }
                     }
                     ServerCommand::Part { channel_name } => {
                         if let Err(e) = client.send_part(&channel_name) {
@@ -136,41 +204,234 @@ pub(super) async fn server_actor(
                             error!(event = "nick_send_failed", command = "NICK", new_nick = %new_nick, error = %e, "failed to send IRC NICK command");
                         }
                     }
+                    ServerCommand::Topic { channel, topic } => {
+                        info!(event = "topic", channel = %channel);
+                        if let Err(e) = client.send(Command::TOPIC(channel, Some(topic))) {
+                            error!("Failed to send topic: {e}");
+                        }
+                    }
+                    ServerCommand::Invite { nickname, channel } => {
+                        info!(event = "invite", nickname = %nickname, channel = %channel);
+                        if let Err(e) = client.send(Command::INVITE(nickname, channel)) {
+                            error!("Failed to send invite: {e}");
+                        }
+                    }
+                    ServerCommand::Raw(line) => {
+                        match line.parse::<Message>() {
+                            Ok(message) => {
+                                if let Err(e) = client.send(message) {
+                                    error!("Failed to send raw message: {e}");
+                                }
+                            }
+                            Err(e) => {
+                                warn!(event = "invalid_raw_line", error = %e, "Failed to parse raw IRC line");
+                            }
+                        }
+                    }
                     ServerCommand::Quit => {
                         if let Err(e) = client.send_quit("bye") {
                             error!("Failed to send quit message: {e}");
                         }
-                        break;
+                        break ActorExit::Quit;
                     }
                 }
             }
         }
+    };
+
+    // 남은 메세지(QUIT 등)를 writer가 모두 보내도록 송신 핸들을 먼저 정리
+    drop(client);
+    if timeout(WRITER_FLUSH_TIMEOUT, &mut writer).await.is_err() {
+        // 응답 없는 소켓에 막힌 writer가 새 연결과 겹치지 않도록 중단
+        warn!(event = "writer_flush_timeout", "Aborting IRC writer");
+        writer.abort();
+    }
+
+    exit
+}
+
+/// 주소 목록을 start 번째부터 순서대로 시도해 처음 성공한 연결 반환
+///
+/// 주소별 실패 이유는 상태에 기록하고, 모두 실패하면 이유를 합쳐서 반환
+async fn connect_endpoints(
+    server_id: ServerId,
+    server_config: &ServerConfig,
+    start: usize,
+    app_handle: &AppHandle,
+) -> Result<Connection, String> {
+    let endpoints = server_config.endpoints();
+    let mut connected = None;
+    let mut failures = Vec::new();
+
+    let ordered = endpoints
+        .iter()
+        .cycle()
+        .skip(start % endpoints.len())
+        .take(endpoints.len());
+    for endpoint in ordered {
+        let result = match timeout(CONNECT_TIMEOUT, connect(server_config, endpoint)).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Connection timed out")),
+        };
+
+        match result {
+            Ok(connection) => {
+                info!(event = "endpoint_connected", endpoint = %endpoint);
+                connected = Some((connection, endpoint.clone()));
+                break;
+            }
+            Err(e) => {
+                warn!(event = "endpoint_failed", endpoint = %endpoint, error = %e);
+                let _ = emit_system_message(
+                    app_handle,
+                    server_id,
+                    &format!("{endpoint} 연결 실패: {e}"),
+                );
+                failures.push(EndpointFailure::new(endpoint.clone(), e.to_string()));
+            }
+        }
     }
 
+    let summary = failures
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let state = app_handle.state::<Arc<KircState>>();
+    if let Some(server) = state.get_server(server_id) {
+        server.set_endpoint_result(connected.as_ref().map(|(_, e)| e.clone()), failures);
+    }
+
+    connected.map(|(connection, _)| connection).ok_or(summary)
+}
+
+/// TOFU: 사용자가 서버 인증서를 승인하거나 거절할 때까지 대기
+async fn wait_for_tls_approval(
+    server_id: ServerId,
+    endpoint: Endpoint,
+    fingerprint: &str,
+    app_handle: &AppHandle,
+) -> bool {
+    let (tx, rx) = oneshot::channel();
     {
         let state = app_handle.state::<Arc<KircState>>();
-        if let Some(server) = state.get_server(server_id) {
-            server.transition_to_disconnected();
+        let Some(server) = state.get_server(server_id) else {
+            return false;
+        };
+        server.set_tls_approval(endpoint.clone(), fingerprint.to_string(), tx);
+    }
+
+    warn!(event = "tls_untrusted", endpoint = %endpoint, fingerprint = %fingerprint, "Waiting for certificate approval");
+    let _ = emit_tls_untrusted(app_handle, server_id, &endpoint, fingerprint);
+
+    let approved = matches!(timeout(TLS_APPROVAL_TIMEOUT, rx).await, Ok(Ok(true)));
+
+    // 시간이 지나면 늦게 온 승인으로 인증서가 고정되지 않도록 정리
+    let state = app_handle.state::<Arc<KircState>>();
+    if let Some(server) = state.get_server(server_id) {
+        server.take_tls_approval();
+    }
+
+    approved
+}
+
+/// CAP 협상을 시작하고 PASS/NICK/USER로 등록 진행
+///
+/// CAP END를 먼저 보내면 SASL 인증을 끼워넣을 수 없으므로 CAP LS부터 시작
+fn register(client: &IrcClient, server_config: &ServerConfig) -> anyhow::Result<()> {
+    let nickname = server_config.nickname().to_string();
+
+    client.send_cap_ls(NegotiationVersion::V302)?;
+    if let Some(password) = server_config.password() {
+        client.send(Command::PASS(password.to_string()))?;
+    }
+    client.send(Command::NICK(nickname.clone()))?;
+    client.send(Command::USER(nickname.clone(), "0".to_string(), nickname))?;
+
+    Ok(())
+}
+
+/// perform 명령을 지연시간에 맞춰 보내고 저장된 채널에 재입장
+///
+/// actor의 sender를 사용하므로 연결이 끊기면 남은 명령은 버려짐
+async fn perform_and_rejoin(
+    tx: UnboundedSender<ServerCommand>,
+    nickname: String,
+    perform: Vec<PerformCommand>,
+    channels: Vec<ChannelId>,
+) {
+    for command in perform {
+        if !command.delay().is_zero() {
+            tokio::time::sleep(command.delay()).await;
+        }
+
+        let line = match command.irc_line(&nickname) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!(event = "invalid_perform_command", error = %e, "Skipping perform command");
+                continue;
+            }
+        };
+        if tx.send(ServerCommand::Raw(line)).is_err() {
+            return;
         }
     }
 
-    let _ = emit_server_status(&app_handle, server_id, ServerStatus::Disconnected);
+    for channel in channels {
+        if tx.send(ServerCommand::Join(channel)).is_err() {
+            return;
+        }
+    }
 }
 
-fn fail_state(server_id: ServerId, app_handle: AppHandle, message: String) {
-    let state = app_handle.state::<Arc<KircState>>();
+/// server-time 태그가 있으면 서버 기준 시각, 없으면 현재 시각 (ms)
+fn message_timestamp(tags: &Option<Vec<Tag>>) -> u64 {
+    tags.iter()
+        .flatten()
+        .find(|Tag(key, _)| key == "time")
+        .and_then(|Tag(_, value)| value.as_deref())
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+        .map(|time| time.timestamp_millis())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()) as u64
+}
 
-    if let Some(server) = state.get_server(server_id) {
-        server.transition_to_failed(message);
+/// 실제로 보낸 메세지를 받은 메세지처럼 처리해 UI에 표시
+///
+/// 대기열에서 취소된 메세지는 writer가 돌려주지 않으므로 표시되지 않음
+fn echo_message(
+    client: &IrcClient,
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    mut message: Message,
+) {
+    let (current_nick, echoed_by_server) = {
+        let state = app_handle.state::<Arc<KircState>>();
+        if let Some(server) = state.get_server(server_id) {
+            (
+                server.current_nickname(),
+                server.is_cap_enabled("echo-message"),
+            )
+        } else {
+            (String::new(), false)
+        }
+    };
+
+    // echo-message가 활성화되면 서버가 보낸 메세지를 다시 돌려줌
+    if echoed_by_server {
+        return;
     }
 
-    let _ = emit_server_status(&app_handle, server_id, ServerStatus::Failed);
+    message.prefix = Some(Prefix::new_from_str(&current_nick));
+    if let Err(e) = handle_message(client, server_id, message, app_handle) {
+        error!("Failed to handle echo message: {e}");
+    }
 }
 
 /// 서버에서 클라이언트로 보낸 메세지 핸들링
 #[instrument(skip(client, app_handle, message), level = "trace")]
 fn handle_message(
-    client: &Client,
+    client: &IrcClient,
     server_id: ServerId,
     message: Message,
     app_handle: &AppHandle,
@@ -178,71 +439,360 @@ fn handle_message(
     let source_nickname = message.source_nickname().unwrap_or("").to_string();
 
     match message.command {
-        Command::PRIVMSG(target, content) => {
-            if let Some(ctcp) = parse_ctcp(&content) {
+        Command::PRIVMSG(target, content) => match parse_ctcp(&content) {
+            Some(CtcpCommand::Action(action)) => {
+                let conversation =
+                    conversation_name(server_id, app_handle, &source_nickname, target)?;
+                emit_ui_event(app_handle)
+                    .action(
+                        server_id,
+                        conversation,
+                        source_nickname,
+                        action,
+                        message_timestamp(&message.tags),
+                    )
+                    .emit()?;
+            }
+            Some(ctcp) => {
                 info!(target = %target, content = %content, "Received CTCP message");
-                handle_ctcp(client, &source_nickname, ctcp);
-            } else {
+                handle_ctcp(
+                    client,
+                    server_id,
+                    app_handle,
+                    &source_nickname,
+                    &target,
+                    ctcp,
+                )?;
+            }
+            None => {
+                if is_echoed_credentials(server_id, app_handle, &source_nickname, &target, &content)
+                {
+                    return Ok(());
+                }
+
+                let conversation =
+                    conversation_name(server_id, app_handle, &source_nickname, target)?;
                 emit_ui_event(app_handle)
-                    .user_message(server_id, target, source_nickname, content)
+                    .user_message(
+                        server_id,
+                        conversation,
+                        source_nickname,
+                        content,
+                        message_timestamp(&message.tags),
+                    )
                     .emit()?;
             }
+        },
+        Command::NOTICE(target, content) => {
+            let timestamp = message_timestamp(&message.tags);
+            handle_notice(
+                server_id,
+                app_handle,
+                source_nickname,
+                &target,
+                content,
+                timestamp,
+            )?;
         }
         Command::JOIN(chanlist, _chankey, _real_name) => {
+            let channel = channel_display_name(server_id, app_handle, chanlist);
             emit_ui_event(app_handle)
-                .join(server_id, chanlist, source_nickname)
+                .join(server_id, channel.clone(), source_nickname.clone())
                 .emit()?;
+
+            let state = app_handle.state::<Arc<KircState>>();
+            let Some(server) = state.get_server(server_id) else {
+                return Ok(());
+            };
+
+            // 들어간 채널의 현재 모드 요청 (RPL_CHANNELMODEIS)
+            if server.is_current_nickname(&source_nickname) {
+                client.send(Command::Raw("MODE".to_string(), vec![channel.clone()]))?;
+            }
+
+            if let Some(user) = server.member_joined(&channel, &source_nickname) {
+                emit_members_changed(
+                    app_handle,
+                    server_id,
+                    &channel,
+                    MembersChange::Joined { user },
+                )?;
+            }
         }
         Command::PART(chanlist, comment) => {
+            let channel = channel_display_name(server_id, app_handle, chanlist);
             emit_ui_event(app_handle)
-                .part(server_id, chanlist, source_nickname, comment)
+                .part(server_id, channel.clone(), source_nickname.clone(), comment)
                 .emit()?;
+
+            member_left(server_id, app_handle, &channel, source_nickname)?;
+        }
+        Command::KICK(channel, nickname, comment) => {
+            let channel = channel_display_name(server_id, app_handle, channel);
+            emit_ui_event(app_handle)
+                .kick(
+                    server_id,
+                    channel.clone(),
+                    nickname.clone(),
+                    source_nickname,
+                    comment,
+                )
+                .emit()?;
+
+            member_left(server_id, app_handle, &channel, nickname.clone())?;
+
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                if server.is_current_nickname(&nickname) {
+                    handle_kicked(&server, server_id, app_handle, &channel)?;
+                }
+            }
+        }
+        Command::INVITE(_nickname, channel) => {
+            emit_ui_event(app_handle)
+                .invite(server_id, channel, source_nickname)
+                .emit()?;
+        }
+        Command::Response(Response::RPL_INVITING, args) => {
+            // 341 <nick> <target nick> <channel>
+            if let [_, nickname, channel] = args.as_slice() {
+                emit_system_message(
+                    app_handle,
+                    server_id,
+                    &format!("{nickname}님을 {channel} 채널에 초대했습니다."),
+                )?;
+            }
         }
         Command::QUIT(comment) => {
+            // GHOST로 끊긴 세션이면 기본 닉네임으로 직접 변경
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                if server.is_primary_nickname(&source_nickname) {
+                    send_primary_nickname(&server)?;
+                }
+            }
+
             emit_ui_event(app_handle)
-                .quit(server_id, source_nickname, comment)
+                .quit(server_id, source_nickname.clone(), comment)
                 .emit()?;
+
+            if let Some(server) = state.get_server(server_id) {
+                for channel in server.member_quit(&source_nickname) {
+                    emit_members_changed(
+                        app_handle,
+                        server_id,
+                        &channel,
+                        MembersChange::Left {
+                            nick: source_nickname.clone(),
+                        },
+                    )?;
+                }
+            }
         }
         Command::NICK(nickname) => {
             // 1. 자기 자신인지 확인
             let state = app_handle.state::<Arc<KircState>>();
             if let Some(server) = state.get_server(server_id) {
-                if server.current_nickname() == source_nickname {
+                if server.is_current_nickname(&source_nickname) {
                     server.set_current_nickname(&nickname);
+
+                    if server.nick_recovery().is_some() && server.is_primary_nickname(&nickname) {
+                        server.set_nick_recovery(None);
+                        emit_nick_recovered(app_handle, server_id, &nickname)?;
+                    }
                 }
             }
 
-            // 지금은 백엔드에서 유저목록 관리 x, 나중에 변경될 수 있음
-            // 2. 모든 채널에서 유저 닉 변경
-            /*for channel in state.channels.values_mut() {
-                if channel.users.remove(&old_nick) {
-                    channel.users.insert(new_nick.clone());
+            // 2. 프론트로 이벤트 emit
+            emit_ui_event(app_handle)
+                .nick(server_id, source_nickname.clone(), nickname.clone())
+                .emit()?;
+
+            // 3. 모든 채널에서 유저 닉 변경
+            if let Some(server) = state.get_server(server_id) {
+                for (channel, user) in server.member_renamed(&source_nickname, &nickname) {
+                    emit_members_changed(
+                        app_handle,
+                        server_id,
+                        &channel,
+                        MembersChange::Renamed {
+                            old_nick: source_nickname.clone(),
+                            user,
+                        },
+                    )?;
                 }
-            }*/
 
-            // 3. 프론트로 이벤트 emit
+                // 4. 열린 개인 대화의 상대 닉 변경
+                if let Some(old_nick) = server.rename_query(&source_nickname, &nickname) {
+                    emit_query_changed(
+                        app_handle,
+                        server_id,
+                        QueryChange::Renamed {
+                            old_nick,
+                            nick: nickname.clone(),
+                        },
+                    )?;
+                }
+            }
+        }
+        Command::Raw(command, params) if command == "MODE" => {
+            // KircCodec이 채널 MODE는 원본 인자 그대로 넘김
+            handle_channel_modes(server_id, app_handle, &source_nickname, &params)?;
+        }
+        Command::UserMODE(target, modes) => {
+            let modestring = unparse_user_modes(&modes);
+            let changes = parse_user_modes(&modestring);
+
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                if server.is_current_nickname(&target) {
+                    server.apply_user_modes(&changes, false);
+                }
+            }
+
             emit_ui_event(app_handle)
-                .nick(server_id, source_nickname, nickname)
+                .mode(server_id, target, source_nickname, changes)
                 .emit()?;
         }
+        Command::Response(Response::RPL_CHANNELMODEIS, args) => {
+            // 324 <nick> <channel> <modestring> <mode arguments>...
+            if let [_, channel, modestring, params @ ..] = args.as_slice() {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    let isupport = server.isupport();
+                    let changes = parse_channel_modes(
+                        modestring,
+                        params,
+                        &isupport.chanmodes(),
+                        &isupport.prefix(),
+                    );
+                    server.set_channel_modes(channel, &changes);
+                }
+            }
+        }
+        Command::Response(Response::RPL_UMODEIS, args) => {
+            // 221 <nick> <user modes>
+            if let Some(modestring) = args.get(1) {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    server.apply_user_modes(&parse_user_modes(modestring), true);
+                }
+            }
+        }
+        Command::Response(Response::RPL_NAMREPLY, args) => {
+            // 353 <nick> <symbol> <channel> :[prefix]<nick>{ [prefix]<nick>}
+            if let [_, _, channel, names] = args.as_slice() {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    server.add_names(channel, names);
+                }
+            }
+        }
+        Command::Response(Response::RPL_ENDOFNAMES, args) => {
+            // 366 <nick> <channel> :End of /NAMES list
+            if let Some(channel) = args.get(1) {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    if let Some(users) = server.finish_names(channel) {
+                        let channel = server.channel_name(channel);
+                        emit_members_changed(
+                            app_handle,
+                            server_id,
+                            &channel,
+                            MembersChange::Reset { users },
+                        )?;
+                    }
+                    if let Some(topic) = server.take_pending_topic(channel) {
+                        emit_joined_topic(
+                            app_handle,
+                            server_id,
+                            server.channel_name(channel),
+                            &topic,
+                        )?;
+                    }
+                }
+            }
+        }
         Command::TOPIC(channel, topic) => {
+            let channel = channel_display_name(server_id, app_handle, channel);
+            let set_at = message_timestamp(&message.tags);
+
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                server.set_channel_topic(
+                    &channel,
+                    topic.clone(),
+                    Some(source_nickname.clone()),
+                    Some(set_at),
+                );
+            }
+
             emit_ui_event(app_handle)
-                .topic(server_id, channel, topic)
+                .topic(
+                    server_id,
+                    channel,
+                    topic,
+                    Some(source_nickname),
+                    Some(set_at),
+                    false,
+                )
                 .emit()?;
         }
+        Command::Response(Response::RPL_TOPIC, args) => {
+            // 332 <nick> <channel> :<topic>, 설정자는 RPL_TOPICWHOTIME에서 받은 뒤 emit,
+            // RPL_TOPICWHOTIME을 보내지 않는 서버면 RPL_ENDOFNAMES에서 emit
+            if let [_, channel, topic] = args.as_slice() {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    server.received_channel_topic(channel, topic.clone());
+                }
+            }
+        }
+        Command::Response(Response::RPL_TOPICWHOTIME, args) => {
+            // 333 <nick> <channel> <setter> <setat (초)>
+            if let [_, channel, set_by, set_at] = args.as_slice() {
+                let set_by = set_by.split('!').next().unwrap_or(set_by).to_string();
+                let set_at = set_at.parse::<u64>().ok().map(|secs| secs * 1000);
+
+                let state = app_handle.state::<Arc<KircState>>();
+                let Some(server) = state.get_server(server_id) else {
+                    return Ok(());
+                };
+                if let Some(topic) = server.set_channel_topic_who_time(channel, set_by, set_at) {
+                    emit_joined_topic(app_handle, server_id, server.channel_name(channel), &topic)?;
+                }
+            }
+        }
         Command::ERROR(message) => {
             warn!(message = %message, "Server command error");
             emit_ui_event(app_handle).error(server_id, message).emit()?;
         }
-        Command::Response(Response::RPL_WELCOME, _) => {
+        Command::Response(Response::RPL_WELCOME, args) => {
             {
                 let state = app_handle.state::<Arc<KircState>>();
                 if let Some(server) = state.get_server(server_id) {
                     server.transition_to_connected();
 
-                    // 기존 채널이 존재하면 연결
-                    for (channel_name, _channel_state) in server.channels() {
-                        server.send_command(ServerCommand::Join(channel_name))?;
+                    // RPL_WELCOME의 대상이 서버가 실제로 부여한 닉네임
+                    let nickname = args
+                        .first()
+                        .cloned()
+                        .unwrap_or_else(|| server.current_nickname());
+                    server.set_current_nickname(&nickname);
+                    let fallback = !server.is_primary_nickname(&nickname);
+                    emit_nickname_acquired(app_handle, server_id, &nickname, fallback)?;
+                    if fallback {
+                        start_nick_recovery(client, server_id, app_handle, &server)?;
+                    }
+
+                    // CAP을 지원하지 않는 서버라도 등록이 끝났으니 협상 종료로 처리
+                    server.update_caps(CapState::finish_negotiation);
+
+                    // perform 명령을 보낸 뒤 기존 채널이 존재하면 연결
+                    let perform = server.config().perform().to_vec();
+                    let channels = server.channels().into_keys().collect();
+                    if let Some(tx) = server.command_sender() {
+                        tokio::spawn(perform_and_rejoin(tx, nickname, perform, channels));
                     }
                 }
             }
@@ -252,11 +802,86 @@ fn handle_message(
             // Optional: Alert system message
             emit_system_message(app_handle, server_id, "서버에 연결되었습니다.")?;
         }
-        Command::Response(Response::ERR_NICKNAMEINUSE, _) => {
-            // 닉네임이 중복된 경우
-            // alt nick으로 재시도
-            // 그래도 안될경우 오류 던지기
-            trace!("닉네임 중복");
+        Command::Response(Response::RPL_ISUPPORT, args) => {
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                server.update_isupport(|isupport| isupport.apply(&args));
+                emit_server_features(
+                    app_handle,
+                    server_id,
+                    server.display_name(),
+                    ServerFeatures::from(&server.isupport()),
+                )?;
+            }
+        }
+        Command::CAP(_, subcommand, field, param) => {
+            handle_cap(client, server_id, subcommand, field, param, app_handle)?;
+        }
+        Command::AUTHENTICATE(data) => {
+            handle_authenticate(client, server_id, &data, app_handle)?;
+        }
+        Command::Response(Response::RPL_LOGGEDIN, args) => {
+            // 900 <nick> <nick>!<ident>@<host> <account> :You are now logged in as <user>
+            if let Some(account) = args.get(2) {
+                info!(event = "sasl_logged_in", account = %account);
+                emit_system_message(
+                    app_handle,
+                    server_id,
+                    &format!("{account} 계정으로 로그인되었습니다."),
+                )?;
+            }
+        }
+        Command::Response(
+            response @ (Response::RPL_SASLSUCCESS
+            | Response::ERR_SASLALREADY
+            | Response::ERR_NICKLOCKED
+            | Response::ERR_SASLFAIL
+            | Response::ERR_SASLTOOLONG
+            | Response::ERR_SASLABORT),
+            args,
+        ) => match sasl_outcome(response, &args) {
+            Ok(()) => {
+                let state = app_handle.state::<Arc<KircState>>();
+                if let Some(server) = state.get_server(server_id) {
+                    server.set_sasl_progress(SaslProgress::Done);
+                    finish_cap_negotiation(client, &server)?;
+                }
+            }
+            Err(reason) => fail_authentication(server_id, app_handle, &reason)?,
+        },
+        Command::PING(server1, server2) => {
+            client.send(Command::PONG(server1, server2))?;
+        }
+        Command::Response(Response::ERR_NICKNAMEINUSE, _)
+        | Command::Response(Response::ERR_ERRONEOUSNICKNAME, _) => {
+            let state = app_handle.state::<Arc<KircState>>();
+            let Some(server) = state.get_server(server_id) else {
+                return Ok(());
+            };
+
+            // 등록 중이 아니면 닉네임 회수 또는 사용자가 요청한 닉네임 변경이 실패한 것
+            if !matches!(server.status(), ServerStatus::Registering) {
+                if server.nick_recovery().is_some() {
+                    server.set_nick_recovery(None);
+                    emit_nick_recovery_failed(app_handle, server_id, "Nickname is still in use")?;
+                } else {
+                    emit_change_nick_failed(app_handle, server_id, "Can't change nickname")?;
+                }
+                return Ok(());
+            }
+
+            match server.next_fallback_nickname() {
+                Some(nickname) => {
+                    info!(event = "nick_fallback", nickname = %nickname, "Nickname rejected, trying fallback");
+                    server.set_current_nickname(&nickname);
+                    client.send(Command::NICK(nickname))?;
+                }
+                None => {
+                    emit_change_nick_failed(app_handle, server_id, "No usable nickname")?;
+                    emit_system_message(app_handle, server_id, "사용 가능한 닉네임이 없습니다.")?;
+                    server.send_command(ServerCommand::Quit)?;
+                }
+            }
         }
         _ => {
             // TODO: Command 다른것도 추가하기
@@ -267,50 +892,628 @@ fn handle_message(
     Ok(())
 }
 
-fn handle_ctcp(client: &Client, source_nickname: &str, ctcp: CtcpCommand) {
+/// 채널 notice는 채널로, 서버/서비스/개인 notice는 서버 버퍼로 보냄
+fn handle_notice(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    source_nickname: String,
+    target: &str,
+    content: String,
+    timestamp: u64,
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+
+    let from_nickserv = server
+        .config()
+        .services()
+        .is_some_and(|services| server.nick_eq(services.nickserv(), &source_nickname));
+    if from_nickserv && server.nick_recovery().is_some() {
+        handle_recovery_reply(server_id, app_handle, &server, &content)?;
+    }
+
+    // 접두사가 없는 notice는 연결한 서버가 보낸 것
+    let nickname = if source_nickname.is_empty() {
+        server.display_name()
+    } else {
+        source_nickname
+    };
+
+    if let Some(reply) = parse_ctcp_reply(&content) {
+        // echo-message로 돌아온 자신의 CTCP 응답은 표시하지 않음
+        if server.is_current_nickname(&nickname) {
+            return Ok(());
+        }
+        info!(event = "ctcp_reply", nick = %nickname, command = %reply.command);
+        let rtt_ms = ping_rtt(&reply, chrono::Utc::now().timestamp_millis());
+        return emit_ui_event(app_handle)
+            .ctcp_reply(server_id, nickname, reply.command, reply.params, rtt_ms)
+            .emit();
+    }
+
+    let channel = server
+        .isupport()
+        .channel_target(target)
+        .map(|channel| server.channel_name(channel));
+
+    emit_ui_event(app_handle)
+        .notice(server_id, channel, nickname, content, timestamp)
+        .emit()
+}
+
+/// 들어갈 때 받은 (또는 TOPIC으로 조회한) 현재 토픽 알림
+fn emit_joined_topic(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    channel: String,
+    topic: &Topic,
+) -> anyhow::Result<()> {
+    emit_ui_event(app_handle)
+        .topic(
+            server_id,
+            channel,
+            Some(topic.text().to_string()),
+            topic.set_by().map(str::to_string),
+            topic.set_at(),
+            true,
+        )
+        .emit()
+}
+
+/// CHANMODES 기준으로 채널 MODE를 파싱해 상태에 반영하고 UI에 알림
+fn handle_channel_modes(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    setter: &str,
+    params: &[String],
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+    let Some((channel, changes, updated)) = apply_channel_modes(&server, params) else {
+        return Ok(());
+    };
+
+    emit_ui_event(app_handle)
+        .mode(server_id, channel.clone(), setter.to_string(), changes)
+        .emit()?;
+
+    for user in updated {
+        emit_members_changed(
+            app_handle,
+            server_id,
+            &channel,
+            MembersChange::Updated { user },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// `<channel> <modestring> <mode arguments>...`를 상태에 반영하고 표시용 채널 이름,
+/// 변경 내용, 권한이 바뀐 멤버 반환
+fn apply_channel_modes(
+    server: &ServerState,
+    params: &[String],
+) -> Option<(String, Vec<ModeChange>, Vec<ChannelUser>)> {
+    let [channel, modestring, args @ ..] = params else {
+        return None;
+    };
+
+    let isupport = server.isupport();
+    let changes = parse_channel_modes(modestring, args, &isupport.chanmodes(), &isupport.prefix());
+    let channel = server.channel_name(channel);
+    let updated = server.apply_channel_modes(&channel, &changes);
+    Some((channel, changes, updated))
+}
+
+/// 강퇴당한 채널은 목록에서 제거, 자동 재입장이 설정되어 있으면 잠시 뒤 다시 JOIN
+fn handle_kicked(
+    server: &ServerState,
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    channel: &str,
+) -> anyhow::Result<()> {
+    let Some(delay) = server.kicked_from_channel(channel) else {
+        return Ok(());
+    };
+
+    emit_system_message(
+        app_handle,
+        server_id,
+        &format!("{}초 후 {channel} 채널에 다시 들어갑니다.", delay.as_secs()),
+    )?;
+
+    if let Some(tx) = server.command_sender() {
+        let channel = channel.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = tx.send(ServerCommand::Join(channel));
+        });
+    }
+
+    Ok(())
+}
+
+/// PART, KICK으로 멤버가 채널에서 나감
+fn member_left(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    channel: &str,
+    nick: String,
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    if state
+        .get_server(server_id)
+        .is_some_and(|server| server.member_left(channel, &nick))
+    {
+        emit_members_changed(app_handle, server_id, channel, MembersChange::Left { nick })?;
+    }
+
+    Ok(())
+}
+
+/// 자신이 NickServ에 보낸 IDENTIFY, 회수 명령 등 비밀번호가 들어있는 메세지인지
+///
+/// echo-message나 로컬 echo로 돌아와도 대화창에 비밀번호를 표시하지 않음
+fn is_echoed_credentials(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    source_nickname: &str,
+    target: &str,
+    content: &str,
+) -> bool {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return false;
+    };
+    if !server.is_current_nickname(source_nickname) || !is_credential_command(content) {
+        return false;
+    }
+
+    let config = server.config();
+    let nickserv = config
+        .services()
+        .map_or("NickServ", |services| services.nickserv());
+    server.nick_eq(nickserv, target)
+}
+
+/// PRIVMSG를 표시할 대화 이름, 채널이 아니면 상대 닉네임의 개인 대화
+///
+/// 받은 메세지는 보낸 사람, 자신이 보낸 메세지(echo)는 target이 대화 상대,
+/// 닉네임 없이 서버가 보낸 메세지는 None (서버 버퍼)
+fn conversation_name(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    source_nickname: &str,
+    target: String,
+) -> anyhow::Result<Option<String>> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(Some(target));
+    };
 
-    if let Some(reply) = get_ctcp_reply(&ctcp) {
-        info!(event = "handle_ctcp_message", ctcp = ?ctcp, reply = %reply);
-        let _ = client.send_notice(source_nickname, &reply);
+    if let Some(channel) = server.isupport().channel_target(&target) {
+        return Ok(Some(server.channel_name(channel)));
+    }
+    if source_nickname.is_empty() {
+        return Ok(None);
+    }
+
+    let peer = if server.is_current_nickname(source_nickname) {
+        &target
     } else {
+        source_nickname
+    };
+    let (nick, opened) = server.open_query(peer);
+    if opened {
+        emit_query_changed(
+            app_handle,
+            server_id,
+            QueryChange::Opened { nick: nick.clone() },
+        )?;
+    }
+
+    Ok(Some(nick))
+}
+
+/// 대소문자만 다른 채널 이름을 사용자가 처음 입력한 표시용 이름으로 맞춤
+fn channel_display_name(server_id: ServerId, app_handle: &AppHandle, channel: String) -> String {
+    let state = app_handle.state::<Arc<KircState>>();
+    match state.get_server(server_id) {
+        Some(server) => server.channel_name(&channel),
+        None => channel,
+    }
+}
+
+/// alternate 닉네임으로 등록되었을 때 NickServ로 기본 닉네임 회수 요청
+///
+/// GHOST는 공유 채널이 없으면 QUIT을 볼 수 없으므로, NickServ 응답이 없어도 잠시 후 NICK 전송
+fn start_nick_recovery(
+    client: &IrcClient,
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    server: &ServerState,
+) -> anyhow::Result<()> {
+    let config = server.config();
+    let Some(services) = config.services() else {
+        return Ok(());
+    };
+
+    info!(event = "nick_recovery", method = ?services.recovery(), "Recovering primary nickname");
+    client.send_privmsg(
+        services.nickserv(),
+        &recovery_message(services.recovery(), config.nickname(), services.password()),
+    )?;
+    let recovery = NickRecovery::new(services.recovery());
+    server.set_nick_recovery(Some(recovery));
+
+    if services.recovery() == NickRecoveryMethod::Ghost {
+        let app_handle = app_handle.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(GHOST_NICK_DELAY).await;
+
+            let state = app_handle.state::<Arc<KircState>>();
+            if let Some(server) = state.get_server(server_id) {
+                // 그 사이 회수가 끝났거나 새 연결에서 다시 시작했으면 무시
+                if server.nick_recovery() == Some(recovery) {
+                    let _ = send_primary_nickname(&server);
+                }
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// GHOST로 기본 닉네임이 비었으면 한 번만 NICK 전송
+fn send_primary_nickname(server: &ServerState) -> anyhow::Result<()> {
+    let Some(recovery) = server.nick_recovery().filter(NickRecovery::needs_nick) else {
+        return Ok(());
+    };
+
+    server.set_nick_recovery(Some(recovery.with_nick_sent()));
+    server.send_command(ServerCommand::Nick(server.config().nickname().to_string()))
+}
+
+/// 회수 명령에 대한 NickServ NOTICE 처리
+fn handle_recovery_reply(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    server: &ServerState,
+    content: &str,
+) -> anyhow::Result<()> {
+    match parse_recovery_reply(content) {
+        Some(RecoveryReply::Succeeded) => send_primary_nickname(server),
+        Some(RecoveryReply::Failed(reason)) => {
+            server.set_nick_recovery(None);
+            emit_nick_recovery_failed(app_handle, server_id, &reason)
+        }
+        None => Ok(()),
+    }
+}
+
+/// NickServ 응답 없이 시간이 지나면 회수 실패 처리
+fn check_nick_recovery(server_id: ServerId, app_handle: &AppHandle) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+
+    if server.nick_recovery().is_some_and(|r| r.is_expired()) {
+        server.set_nick_recovery(None);
+        emit_nick_recovery_failed(app_handle, server_id, "NickServ did not respond")?;
+    }
+
+    Ok(())
+}
+
+/// IRCv3 capability 협상 처리
+///
+/// 등록 중에는 LS 응답이 끝나면 설정된 capability를 요청하고,
+/// 요청한 capability의 응답과 SASL 인증이 모두 끝나면 CAP END 전송
+fn handle_cap(
+    client: &IrcClient,
+    server_id: ServerId,
+    subcommand: CapSubCommand,
+    field: Option<String>,
+    param: Option<String>,
+    app_handle: &AppHandle,
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+    let config = server.config();
+    let (more, caps) = cap_params(field.as_deref(), param.as_deref());
+
+    match subcommand {
+        CapSubCommand::LS => {
+            server.update_caps(|c| c.add_available(caps));
+
+            // 여러 줄 LS는 마지막 줄을 받은 뒤 한번에 요청
+            if more {
+                return Ok(());
+            }
+
+            request_caps(client, &server, config.capabilities())?;
+
+            if config.sasl().is_some() {
+                if !server.update_caps(|c| c.is_available("sasl")) {
+                    return fail_authentication(
+                        server_id,
+                        app_handle,
+                        "Server does not support SASL",
+                    );
+                }
+
+                // REQ는 줄 단위로 전부 ACK/NAK 되므로 다른 capability 거부에 휘말리지 않게 따로 요청
+                request_caps(client, &server, &["sasl"])?;
+                server.set_sasl_progress(SaslProgress::Requested);
+            }
+        }
+        CapSubCommand::NEW => {
+            info!(event = "cap_new", caps = %caps);
+            server.update_caps(|c| c.add_available(caps));
+            request_caps(client, &server, config.capabilities())?;
+        }
+        CapSubCommand::DEL => {
+            info!(event = "cap_del", caps = %caps);
+            let removed = server.update_caps(|c| c.remove_available(caps));
+            if !removed.is_empty() {
+                emit_capabilities_changed(app_handle, server_id, server.enabled_caps())?;
+            }
+        }
+        CapSubCommand::ACK => {
+            server.update_caps(|c| c.acknowledge(caps));
+
+            if server.sasl_progress() == SaslProgress::Requested && server.is_cap_enabled("sasl") {
+                if let Some(sasl) = config.sasl() {
+                    client.send_sasl(sasl.mechanism())?;
+                    server.set_sasl_progress(SaslProgress::Authenticating);
+                }
+            }
+
+            emit_capabilities_changed(app_handle, server_id, server.enabled_caps())?;
+        }
+        CapSubCommand::NAK => {
+            warn!(event = "cap_nak", caps = %caps);
+            server.update_caps(|c| c.reject(caps));
+
+            if server.sasl_progress() == SaslProgress::Requested
+                && parse_cap_list(caps).any(|(name, _)| name == "sasl")
+            {
+                return fail_authentication(server_id, app_handle, "Server rejected SASL");
+            }
+        }
+        _ => {
+            debug!(event = "unprocessed_cap_message", subcommand = ?subcommand, caps = %caps);
+        }
+    }
+
+    finish_cap_negotiation(client, &server)
+}
+
+/// 서버가 제공하는 capability 중 아직 요청하지 않은 것만 REQ 전송
+fn request_caps<S: AsRef<str>>(
+    client: &IrcClient,
+    server: &ServerState,
+    wanted: &[S],
+) -> anyhow::Result<()> {
+    let requested = server.update_caps(|c| c.request(wanted));
+    for line in req_lines(&requested) {
+        client.send_cap_req(line)?;
+    }
+
+    Ok(())
+}
+
+/// 등록 중이고 대기중인 REQ와 SASL 인증이 모두 끝났으면 CAP END 전송
+fn finish_cap_negotiation(client: &IrcClient, server: &ServerState) -> anyhow::Result<()> {
+    if matches!(
+        server.sasl_progress(),
+        SaslProgress::Requested | SaslProgress::Authenticating
+    ) {
+        return Ok(());
+    }
+
+    let should_end = server.update_caps(|c| {
+        if c.is_negotiating() && !c.is_pending() {
+            c.finish_negotiation();
+            true
+        } else {
+            false
+        }
+    });
+
+    if should_end {
+        client.send_cap_end()?;
+    }
+
+    Ok(())
+}
+
+/// 서버의 `AUTHENTICATE +` 요청에 인증 정보 전송
+fn handle_authenticate(
+    client: &IrcClient,
+    server_id: ServerId,
+    data: &str,
+    app_handle: &AppHandle,
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+
+    if data != "+" || server.sasl_progress() != SaslProgress::Authenticating {
+        return Ok(());
+    }
+
+    let payload = match server.config().sasl() {
+        Some(SaslConfig::Plain { account, password }) => plain_payload(account, password),
+        // 인증서로 이미 신원을 증명했으므로 빈 authzid 전송
+        Some(SaslConfig::External) => String::new(),
+        None => return Ok(()),
+    };
+
+    for chunk in authenticate_chunks(&payload) {
+        client.send_sasl(chunk)?;
+    }
+
+    Ok(())
+}
+
+/// SASL 결과 numeric, 로그인되지 않은 채로 끝나면 (중단, 잠긴 닉네임 포함) 실패 이유
+fn sasl_outcome(response: Response, args: &[String]) -> Result<(), String> {
+    match response {
+        Response::RPL_SASLSUCCESS | Response::ERR_SASLALREADY => Ok(()),
+        _ => Err(args
+            .last()
+            .cloned()
+            .unwrap_or_else(|| "SASL authentication failed".to_string())),
+    }
+}
+
+/// SASL 인증 실패시 등록을 계속하지 않고 연결 종료
+fn fail_authentication(
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    reason: &str,
+) -> anyhow::Result<()> {
+    warn!(event = "sasl_failed", reason = %reason);
+
+    {
+        let state = app_handle.state::<Arc<KircState>>();
+        if let Some(server) = state.get_server(server_id) {
+            server.send_command(ServerCommand::Quit)?;
+            server.transition_to_auth_failed(reason.to_string());
+        }
+    }
+
+    emit_server_status(app_handle, server_id, ServerStatus::AuthFailed)?;
+    emit_system_message(
+        app_handle,
+        server_id,
+        &format!("SASL 인증에 실패했습니다: {reason}"),
+    )?;
+
+    Ok(())
+}
+
+fn handle_ctcp(
+    client: &IrcClient,
+    server_id: ServerId,
+    app_handle: &AppHandle,
+    source_nickname: &str,
+    target: &str,
+    ctcp: CtcpCommand,
+) -> anyhow::Result<()> {
+    let state = app_handle.state::<Arc<KircState>>();
+    let Some(server) = state.get_server(server_id) else {
+        return Ok(());
+    };
+
+    // echo-message로 돌아온 자신의 요청에는 응답하지 않음
+    if server.is_current_nickname(source_nickname) {
+        return Ok(());
+    }
+
+    let config = server.config();
+    let Some(reply) = get_ctcp_reply(&ctcp, config.ctcp(), &server.current_nickname()) else {
         if let CtcpCommand::Unknown(msg) = ctcp {
             warn!(event = "unknown_ctcp_command", message = %msg)
         }
+        return Ok(());
+    };
+
+    let channel = server.isupport().channel_target(target).map(str::to_string);
+    match server.check_ctcp(source_nickname, channel.is_some()) {
+        CtcpVerdict::Reply => {
+            info!(event = "handle_ctcp_message", ctcp = ?ctcp, reply = %reply);
+            let _ = client.send_notice(source_nickname, &reply);
+        }
+        CtcpVerdict::Ignore { notify } => {
+            warn!(event = "ctcp_flood", source = %source_nickname, target = %target, flood = ?notify);
+            let message = match (notify, channel) {
+                (None, _) => return Ok(()),
+                (Some(CtcpFlood::Channel), Some(channel)) => {
+                    format!("{channel} 채널의 CTCP flood를 무시합니다.")
+                }
+                (Some(CtcpFlood::Global), _) => "CTCP 요청이 너무 많아 무시합니다.".to_string(),
+                (Some(_), _) => format!("{source_nickname}의 CTCP flood를 무시합니다."),
+            };
+            emit_system_message(app_handle, server_id, &message)?;
+        }
     }
+
+    Ok(())
 }
 
-fn get_ctcp_reply(ctcp: &CtcpCommand) -> Option<String> {
+/// 설정에서 끈 명령이면 응답하지 않음
+fn get_ctcp_reply(ctcp: &CtcpCommand, config: &CtcpConfig, nickname: &str) -> Option<String> {
+    if !config.is_enabled(ctcp.name()) {
+        return None;
+    }
+
     match ctcp {
-        CtcpCommand::Version => Some("\x01VERSION kirc-rs v0.1\x01".to_string()),
-        CtcpCommand::Ping(payload) => Some(format!("\x01PING {}\x01", payload)),
+        CtcpCommand::Version => Some(ctcp_message("VERSION", config.version())),
+        CtcpCommand::Ping(payload) => Some(ctcp_message("PING", payload)),
         CtcpCommand::Time => {
             let now = chrono::Local::now().to_rfc2822();
-            Some(format!("\x01TIME {}\x01", now))
+            Some(ctcp_message("TIME", &now))
         }
-        CtcpCommand::Unknown(_) => None,
+        CtcpCommand::ClientInfo => {
+            let commands: Vec<&str> = SUPPORTED_COMMANDS
+                .into_iter()
+                .filter(|command| config.is_enabled(command))
+                .collect();
+            Some(ctcp_message("CLIENTINFO", &commands.join(" ")))
+        }
+        CtcpCommand::Source => Some(ctcp_message("SOURCE", SOURCE_URL)),
+        CtcpCommand::UserInfo => Some(ctcp_message(
+            "USERINFO",
+            config.userinfo().unwrap_or(nickname),
+        )),
+        CtcpCommand::Finger => Some(ctcp_message("FINGER", config.finger().unwrap_or(nickname))),
+        CtcpCommand::Action(_) | CtcpCommand::Unknown(_) => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kirc::connection::KircCodec;
     use crate::kirc::ctcp::CtcpCommand;
+    use crate::kirc::state::server::ServerRuntime;
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+
+    fn reply(ctcp: CtcpCommand) -> Option<String> {
+        get_ctcp_reply(&ctcp, &CtcpConfig::default(), "kirc")
+    }
+
+    fn config(json: &str) -> CtcpConfig {
+        serde_json::from_str(json).unwrap()
+    }
 
     #[test]
     fn test_get_ctcp_reply_version() {
-        let reply = get_ctcp_reply(&CtcpCommand::Version);
+        let reply = reply(CtcpCommand::Version);
         assert_eq!(reply, Some("\x01VERSION kirc v0.1\x01".to_string()));
     }
 
     #[test]
     fn test_get_ctcp_reply_ping() {
-        let reply = get_ctcp_reply(&CtcpCommand::Ping("12345".to_string()));
+        let reply = reply(CtcpCommand::Ping("12345".to_string()));
         assert_eq!(reply, Some("\x01PING 12345\x01".to_string()));
     }
 
     #[test]
     fn test_get_ctcp_reply_time() {
-        let reply = get_ctcp_reply(&CtcpCommand::Time);
+        let reply = reply(CtcpCommand::Time);
         assert!(reply.is_some());
         let reply_str = reply.unwrap();
         assert!(reply_str.starts_with("\x01TIME "));
@@ -319,7 +1522,115 @@ mod tests {
 
     #[test]
     fn test_get_ctcp_reply_unknown() {
-        let reply = get_ctcp_reply(&CtcpCommand::Unknown("FOO".to_string()));
+        let reply = reply(CtcpCommand::Unknown("FOO".to_string()));
         assert_eq!(reply, None);
     }
+
+    #[test]
+    fn test_get_ctcp_reply_info() {
+        assert_eq!(
+            reply(CtcpCommand::UserInfo),
+            Some("\x01USERINFO kirc\x01".to_string())
+        );
+        assert_eq!(
+            reply(CtcpCommand::Source),
+            Some(format!("\x01SOURCE {SOURCE_URL}\x01"))
+        );
+
+        let config = config(r#"{"finger":"away"}"#);
+        assert_eq!(
+            get_ctcp_reply(&CtcpCommand::Finger, &config, "kirc"),
+            Some("\x01FINGER away\x01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_ctcp_reply_configured() {
+        let config = config(r#"{"version":"custom 1.0","disabled":["time","USERINFO"]}"#);
+
+        assert_eq!(
+            get_ctcp_reply(&CtcpCommand::Version, &config, "kirc"),
+            Some("\x01VERSION custom 1.0\x01".to_string())
+        );
+        assert_eq!(get_ctcp_reply(&CtcpCommand::Time, &config, "kirc"), None);
+        assert_eq!(
+            get_ctcp_reply(&CtcpCommand::ClientInfo, &config, "kirc"),
+            Some("\x01CLIENTINFO ACTION CLIENTINFO FINGER PING SOURCE VERSION\x01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_channel_modes_keep_params_after_unknown_mode() {
+        let config = ServerConfig::new(
+            "irc.example.com".to_string(),
+            6697,
+            true,
+            "kirc".to_string(),
+        );
+        let server = ServerState::new(ServerRuntime::Disconnected, config);
+        server.update_isupport(|isupport| {
+            isupport.apply(&["kirc".to_string(), "CHANMODES=beI,k,lj,imnst".to_string()])
+        });
+        server.member_joined("#c", "kirc");
+        server.add_names("#c", "@kirc bob");
+        server.finish_names("#c");
+
+        let message = KircCodec::new()
+            .unwrap()
+            .decode(&mut BytesMut::from(":op!o@host MODE #c +jv 3:5 bob\r\n"))
+            .unwrap()
+            .unwrap();
+        let Command::Raw(command, params) = message.command else {
+            panic!("channel MODE should keep raw params");
+        };
+        assert_eq!(command, "MODE");
+
+        let (channel, changes, updated) = apply_channel_modes(&server, &params).unwrap();
+        assert_eq!(channel, "#c");
+        assert_eq!(
+            changes,
+            vec![
+                ModeChange {
+                    set: true,
+                    mode: 'j',
+                    arg: Some("3:5".to_string()),
+                },
+                ModeChange {
+                    set: true,
+                    mode: 'v',
+                    arg: Some("bob".to_string()),
+                },
+            ]
+        );
+        assert_eq!(updated.len(), 1);
+        assert_eq!(serde_json::to_value(&updated[0]).unwrap()["prefix"], "+");
+    }
+
+    fn sasl_result(line: &str) -> Result<(), String> {
+        let message: Message = line.parse().unwrap();
+        let Command::Response(response, args) = message.command else {
+            panic!("not a numeric");
+        };
+        sasl_outcome(response, &args)
+    }
+
+    #[test]
+    fn test_sasl_outcome() {
+        assert_eq!(
+            sasl_result(":srv 903 kirc :SASL authentication successful"),
+            Ok(())
+        );
+        assert_eq!(
+            sasl_result(":srv 907 kirc :You have already authenticated"),
+            Ok(())
+        );
+        assert_eq!(
+            sasl_result(":srv 902 kirc :You must use a nick assigned to you"),
+            Err("You must use a nick assigned to you".to_string())
+        );
+        assert_eq!(
+            sasl_result(":srv 906 kirc :SASL authentication aborted"),
+            Err("SASL authentication aborted".to_string())
+        );
+    }
 }
@@ -1,21 +1,69 @@
+/// 설정하지 않았을 때의 VERSION 응답
+pub(in crate::kirc) const DEFAULT_VERSION: &str = "kirc v0.1";
+pub(super) const SOURCE_URL: &str = "https://github.com/4BMtolobPL/irc-client-rs";
+/// CLIENTINFO로 알려줄 지원 명령
+pub(super) const SUPPORTED_COMMANDS: [&str; 8] = [
+    "ACTION",
+    "CLIENTINFO",
+    "FINGER",
+    "PING",
+    "SOURCE",
+    "TIME",
+    "USERINFO",
+    "VERSION",
+];
+
 #[derive(Debug, PartialEq)]
 pub(super) enum CtcpCommand {
     Version,
     Ping(String),
     Time,
+    ClientInfo,
+    Source,
+    UserInfo,
+    Finger,
+    /// `/me`, 응답하지 않고 메세지로 표시
+    Action(String),
     Unknown(String),
 }
 
-pub(super) fn parse_ctcp(message: &str) -> Option<CtcpCommand> {
-    if !message.starts_with('\x01') || !message.ends_with('\x01') {
-        return None;
+/// NOTICE로 돌아온 CTCP 응답 (`\x01VERSION kirc 1.0\x01`)
+#[derive(Debug, PartialEq)]
+pub(super) struct CtcpReply {
+    pub(super) command: String,
+    pub(super) params: String,
+}
+
+impl CtcpCommand {
+    /// 대문자 명령 이름, 설정에서 응답을 끌 때 사용
+    pub(super) fn name(&self) -> &str {
+        match self {
+            CtcpCommand::Version => "VERSION",
+            CtcpCommand::Ping(_) => "PING",
+            CtcpCommand::Time => "TIME",
+            CtcpCommand::ClientInfo => "CLIENTINFO",
+            CtcpCommand::Source => "SOURCE",
+            CtcpCommand::UserInfo => "USERINFO",
+            CtcpCommand::Finger => "FINGER",
+            CtcpCommand::Action(_) => "ACTION",
+            CtcpCommand::Unknown(inner) => inner.split(' ').next().unwrap_or(inner),
+        }
     }
+}
 
-    let inner = &message[1..message.len() - 1];
-    if inner.is_empty() {
+/// `\x01`로 감싸진 CTCP 메세지의 내용
+fn ctcp_inner(message: &str) -> Option<&str> {
+    if message.len() < 2 || !message.starts_with('\x01') || !message.ends_with('\x01') {
         return None;
     }
 
+    let inner = &message[1..message.len() - 1];
+    (!inner.is_empty()).then_some(inner)
+}
+
+pub(super) fn parse_ctcp(message: &str) -> Option<CtcpCommand> {
+    let inner = ctcp_inner(message)?;
+
     let mut parts = inner.splitn(2, ' ');
 
     let cmd = parts.next()?.to_uppercase();
@@ -25,10 +73,44 @@ pub(super) fn parse_ctcp(message: &str) -> Option<CtcpCommand> {
         "VERSION" => Some(CtcpCommand::Version),
         "PING" => Some(CtcpCommand::Ping(arg)),
         "TIME" => Some(CtcpCommand::Time),
+        "CLIENTINFO" => Some(CtcpCommand::ClientInfo),
+        "SOURCE" => Some(CtcpCommand::Source),
+        "USERINFO" => Some(CtcpCommand::UserInfo),
+        "FINGER" => Some(CtcpCommand::Finger),
+        "ACTION" => Some(CtcpCommand::Action(arg)),
         _ => Some(CtcpCommand::Unknown(inner.to_string())),
     }
 }
 
+/// `\x01COMMAND params\x01` 형태로 감쌈
+pub(super) fn ctcp_message(command: &str, params: &str) -> String {
+    if params.is_empty() {
+        format!("\x01{command}\x01")
+    } else {
+        format!("\x01{command} {params}\x01")
+    }
+}
+
+/// 보낸 시각(ms)을 담은 PING 응답의 왕복 시간
+pub(super) fn ping_rtt(reply: &CtcpReply, now_ms: i64) -> Option<u64> {
+    if reply.command != "PING" {
+        return None;
+    }
+
+    let sent_ms: i64 = reply.params.trim().parse().ok()?;
+    u64::try_from(now_ms - sent_ms).ok()
+}
+
+pub(super) fn parse_ctcp_reply(message: &str) -> Option<CtcpReply> {
+    let inner = ctcp_inner(message)?;
+    let (command, params) = inner.split_once(' ').unwrap_or((inner, ""));
+
+    Some(CtcpReply {
+        command: command.to_uppercase(),
+        params: params.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,6 +153,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_ctcp_info_commands() {
+        assert_eq!(
+            parse_ctcp("\x01CLIENTINFO\x01"),
+            Some(CtcpCommand::ClientInfo)
+        );
+        assert_eq!(parse_ctcp("\x01source\x01"), Some(CtcpCommand::Source));
+        assert_eq!(parse_ctcp("\x01USERINFO\x01"), Some(CtcpCommand::UserInfo));
+        assert_eq!(parse_ctcp("\x01FINGER\x01"), Some(CtcpCommand::Finger));
+        assert_eq!(
+            parse_ctcp("\x01DCC SEND file\x01")
+                .as_ref()
+                .map(CtcpCommand::name),
+            Some("DCC")
+        );
+    }
+
+    #[test]
+    fn test_parse_ctcp_action() {
+        assert_eq!(
+            parse_ctcp("\x01ACTION waves hello\x01"),
+            Some(CtcpCommand::Action("waves hello".to_string()))
+        );
+        assert_eq!(
+            parse_ctcp(&ctcp_message("ACTION", "waves")),
+            Some(CtcpCommand::Action("waves".to_string()))
+        );
+        assert_eq!(ctcp_message("VERSION", ""), "\x01VERSION\x01");
+    }
+
+    #[test]
+    fn test_parse_ctcp_reply() {
+        assert_eq!(
+            parse_ctcp_reply("\x01VERSION irssi 1.4\x01"),
+            Some(CtcpReply {
+                command: "VERSION".to_string(),
+                params: "irssi 1.4".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_ctcp_reply("\x01ping 123\x01"),
+            Some(CtcpReply {
+                command: "PING".to_string(),
+                params: "123".to_string(),
+            })
+        );
+        assert_eq!(parse_ctcp_reply("*** Looking up your hostname"), None);
+    }
+
+    #[test]
+    fn test_ping_rtt() {
+        let reply = parse_ctcp_reply("\x01PING 1000\x01").unwrap();
+        assert_eq!(ping_rtt(&reply, 1250), Some(250));
+        // 다른 클라이언트가 보낸 값이거나 미래 시각이면 계산하지 않음
+        assert_eq!(ping_rtt(&reply, 900), None);
+
+        let reply = parse_ctcp_reply("\x01PING abc\x01").unwrap();
+        assert_eq!(ping_rtt(&reply, 1250), None);
+
+        let reply = parse_ctcp_reply("\x01TIME 1000\x01").unwrap();
+        assert_eq!(ping_rtt(&reply, 1250), None);
+    }
+
     #[test]
     fn test_parse_ctcp_ping_no_arg() {
         assert_eq!(
@@ -1,8 +1,15 @@
 use crate::kirc::emits::payload::{
-    ChangeNickFailedPayload, ChannelLockChangedEvent, ServerDetail, ServerStatusPayload,
-    SystemMessagePayload, UIEventPayload,
+    CapabilitiesChangedPayload, ChangeNickFailedPayload, ChannelLockChangedEvent,
+    MembersChangedPayload, NickRecoveredPayload, NicknameAcquiredPayload, QueryChangedPayload,
+    SendQueuePayload, ServerDetail, ServerFeaturesPayload, ServerRemovedPayload,
+    ServerStatusPayload, SystemMessagePayload, TlsUntrustedPayload, UIEventPayload,
 };
+use crate::kirc::isupport::ServerFeatures;
+use crate::kirc::modes::ModeChange;
+use crate::kirc::state::channel::ChannelUser;
+use crate::kirc::types::server::Endpoint;
 use crate::kirc::types::{ServerId, ServerStatus};
+use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 use tracing::trace;
 
@@ -32,6 +39,145 @@ pub(super) fn emit_server_added(
     Ok(())
 }
 
+pub(super) fn emit_server_updated(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    host: &str,
+    port: u16,
+    tls: bool,
+    nickname: &str,
+    status: ServerStatus,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_server_updated");
+
+    app_handle.emit(
+        "kirc:server_updated",
+        ServerDetail::new(
+            server_id,
+            host.to_string(),
+            port,
+            tls,
+            nickname.to_string(),
+            status,
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// 속도 제한으로 대기 중인 메세지 줄 수
+pub(super) fn emit_send_queue(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    depth: usize,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_send_queue");
+
+    app_handle.emit("kirc:send_queue", SendQueuePayload::new(server_id, depth))?;
+
+    Ok(())
+}
+
+pub(super) fn emit_server_removed(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_server_removed");
+
+    app_handle.emit("kirc:server_removed", ServerRemovedPayload::new(server_id))?;
+
+    Ok(())
+}
+
+/// 채널 멤버 목록 변경 내용
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub(super) enum MembersChange {
+    /// NAMES 응답으로 전체 목록을 새로 받음
+    Reset {
+        users: Vec<ChannelUser>,
+    },
+    Joined {
+        user: ChannelUser,
+    },
+    Left {
+        nick: String,
+    },
+    Renamed {
+        old_nick: String,
+        user: ChannelUser,
+    },
+    /// 권한(prefix)이 바뀜
+    Updated {
+        user: ChannelUser,
+    },
+}
+
+pub(super) fn emit_members_changed(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    channel: &str,
+    change: MembersChange,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_members_changed");
+
+    app_handle.emit(
+        "kirc:members_changed",
+        MembersChangedPayload::new(server_id, channel.to_string(), change),
+    )?;
+
+    Ok(())
+}
+
+/// 개인 대화(query) 목록 변경 내용
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub(super) enum QueryChange {
+    Opened {
+        nick: String,
+    },
+    Closed {
+        nick: String,
+    },
+    /// 상대가 닉네임을 바꿈
+    Renamed {
+        old_nick: String,
+        nick: String,
+    },
+}
+
+pub(super) fn emit_query_changed(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    change: QueryChange,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_query_changed");
+
+    app_handle.emit(
+        "kirc:query_changed",
+        QueryChangedPayload::new(server_id, change),
+    )?;
+
+    Ok(())
+}
+
+/// ISUPPORT가 갱신됨, name은 NETWORK 또는 서버 주소
+pub(super) fn emit_server_features(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    name: String,
+    features: ServerFeatures,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_server_features");
+
+    app_handle.emit(
+        "kirc:server_features",
+        ServerFeaturesPayload::new(server_id, name, features),
+    )?;
+
+    Ok(())
+}
+
 pub(super) fn emit_server_status(
     app_handle: &AppHandle,
     server_id: ServerId,
@@ -63,6 +209,21 @@ pub(super) fn emit_channel_lock_changed(
     Ok(())
 }
 
+pub(super) fn emit_capabilities_changed(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    capabilities: Vec<String>,
+) -> anyhow::Result<()> {
+    trace!("Emit emit_capabilities_changed");
+
+    app_handle.emit(
+        "kirc:capabilities_changed",
+        CapabilitiesChangedPayload::new(server_id, capabilities),
+    )?;
+
+    Ok(())
+}
+
 pub(super) fn emit_ui_event(app_handle: &AppHandle) -> UIEventBuilder {
     UIEventBuilder::new(app_handle.clone())
 }
@@ -94,16 +255,37 @@ impl UIEventBuilder {
     pub(super) fn user_message(
         mut self,
         server_id: ServerId,
-        channel: String,
+        channel: Option<String>,
         nickname: String,
         content: String,
+        timestamp: u64,
     ) -> Self {
         self.payload = Some(UIEventPayload::UserMessage {
             server_id,
             channel,
             nick: nickname,
             content,
-            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            timestamp,
+        });
+
+        self
+    }
+
+    /// CTCP ACTION, channel은 채널 또는 개인 대화 상대
+    pub(super) fn action(
+        mut self,
+        server_id: ServerId,
+        channel: Option<String>,
+        nickname: String,
+        content: String,
+        timestamp: u64,
+    ) -> Self {
+        self.payload = Some(UIEventPayload::Action {
+            server_id,
+            channel,
+            nick: nickname,
+            content,
+            timestamp,
         });
 
         self
@@ -161,16 +343,108 @@ impl UIEventBuilder {
         self
     }
 
+    /// channel이 None이면 서버 버퍼 (서버, 서비스, 개인 notice)
+    pub(super) fn notice(
+        mut self,
+        server_id: ServerId,
+        channel: Option<String>,
+        nickname: String,
+        content: String,
+        timestamp: u64,
+    ) -> Self {
+        self.payload = Some(UIEventPayload::Notice {
+            server_id,
+            channel,
+            nick: nickname,
+            content,
+            timestamp,
+        });
+
+        self
+    }
+
+    pub(super) fn ctcp_reply(
+        mut self,
+        server_id: ServerId,
+        nickname: String,
+        command: String,
+        params: String,
+        rtt_ms: Option<u64>,
+    ) -> Self {
+        self.payload = Some(UIEventPayload::CtcpReply {
+            server_id,
+            nick: nickname,
+            command,
+            params,
+            rtt_ms,
+        });
+
+        self
+    }
+
+    pub(super) fn kick(
+        mut self,
+        server_id: ServerId,
+        channel: String,
+        nickname: String,
+        by: String,
+        reason: Option<String>,
+    ) -> Self {
+        self.payload = Some(UIEventPayload::Kick {
+            server_id,
+            channel,
+            nick: nickname,
+            by,
+            reason,
+        });
+
+        self
+    }
+
+    pub(super) fn invite(mut self, server_id: ServerId, channel: String, by: String) -> Self {
+        self.payload = Some(UIEventPayload::Invite {
+            server_id,
+            channel,
+            by,
+        });
+
+        self
+    }
+
+    pub(super) fn mode(
+        mut self,
+        server_id: ServerId,
+        target: String,
+        setter: String,
+        changes: Vec<ModeChange>,
+    ) -> Self {
+        self.payload = Some(UIEventPayload::Mode {
+            server_id,
+            target,
+            setter,
+            changes,
+        });
+
+        self
+    }
+
+    /// on_join이면 채널에 들어갈 때 받은 기존 토픽 (RPL_TOPIC, RPL_TOPICWHOTIME)
     pub(super) fn topic(
         mut self,
         server_id: ServerId,
         channel: String,
         topic: Option<String>,
+        set_by: Option<String>,
+        set_at: Option<u64>,
+        on_join: bool,
     ) -> Self {
         self.payload = Some(UIEventPayload::Topic {
             server_id,
             channel,
             topic,
+            set_by,
+            set_at,
+            on_join,
         });
 
         self
@@ -210,7 +484,67 @@ pub(super) fn emit_change_nick_failed(
     Ok(())
 }
 
+/// 등록 완료 후 실제로 얻은 닉네임 (fallback 여부 포함)
+pub(super) fn emit_nickname_acquired(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    nickname: &str,
+    fallback: bool,
+) -> anyhow::Result<()> {
+    app_handle.emit(
+        "kirc:nickname_acquired",
+        NicknameAcquiredPayload::new(server_id, nickname, fallback),
+    )?;
+
+    Ok(())
+}
+
+/// NickServ로 기본 닉네임을 되찾음
+pub(super) fn emit_nick_recovered(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    nickname: &str,
+) -> anyhow::Result<()> {
+    app_handle.emit(
+        "kirc:nick_recovered",
+        NickRecoveredPayload::new(server_id, nickname),
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn emit_nick_recovery_failed(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    reason: &str,
+) -> anyhow::Result<()> {
+    app_handle.emit(
+        "kirc:nick_recovery_failed",
+        ChangeNickFailedPayload::new(server_id, reason),
+    )?;
+
+    Ok(())
+}
+
+/// TOFU: 처음 보는 서버 인증서, accept_tls_certificate로 승인할 때까지 연결 대기
+pub(super) fn emit_tls_untrusted(
+    app_handle: &AppHandle,
+    server_id: ServerId,
+    endpoint: &Endpoint,
+    fingerprint: &str,
+) -> anyhow::Result<()> {
+    app_handle.emit(
+        "kirc:tls_untrusted",
+        TlsUntrustedPayload::new(server_id, endpoint.to_string(), fingerprint),
+    )?;
+
+    Ok(())
+}
+
 mod payload {
+    use crate::kirc::emits::{MembersChange, QueryChange};
+    use crate::kirc::isupport::ServerFeatures;
+    use crate::kirc::modes::ModeChange;
     use crate::kirc::types::{ChannelId, ServerId, ServerStatus};
     use serde::Serialize;
 
@@ -245,6 +579,80 @@ mod payload {
         }
     }
 
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct MembersChangedPayload {
+        server_id: ServerId,
+        channel: ChannelId,
+        change: MembersChange,
+    }
+
+    impl MembersChangedPayload {
+        pub(super) fn new(server_id: ServerId, channel: ChannelId, change: MembersChange) -> Self {
+            Self {
+                server_id,
+                channel,
+                change,
+            }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct QueryChangedPayload {
+        server_id: ServerId,
+        change: QueryChange,
+    }
+
+    impl QueryChangedPayload {
+        pub(super) fn new(server_id: ServerId, change: QueryChange) -> Self {
+            Self { server_id, change }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct ServerFeaturesPayload {
+        server_id: ServerId,
+        name: String,
+        features: ServerFeatures,
+    }
+
+    impl ServerFeaturesPayload {
+        pub(super) fn new(server_id: ServerId, name: String, features: ServerFeatures) -> Self {
+            Self {
+                server_id,
+                name,
+                features,
+            }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct SendQueuePayload {
+        server_id: ServerId,
+        depth: usize,
+    }
+
+    impl SendQueuePayload {
+        pub(super) fn new(server_id: ServerId, depth: usize) -> Self {
+            Self { server_id, depth }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct ServerRemovedPayload {
+        server_id: ServerId,
+    }
+
+    impl ServerRemovedPayload {
+        pub(super) fn new(server_id: ServerId) -> Self {
+            Self { server_id }
+        }
+    }
+
     #[derive(Serialize, Clone)]
     #[serde(rename_all = "camelCase")]
     pub(super) struct ServerStatusPayload {
@@ -258,6 +666,22 @@ mod payload {
         }
     }
 
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct CapabilitiesChangedPayload {
+        server_id: ServerId,
+        capabilities: Vec<String>,
+    }
+
+    impl CapabilitiesChangedPayload {
+        pub(super) fn new(server_id: ServerId, capabilities: Vec<String>) -> Self {
+            Self {
+                server_id,
+                capabilities,
+            }
+        }
+    }
+
     #[derive(Serialize, Clone)]
     #[serde(rename_all = "camelCase")]
     pub(super) struct ChannelLockChangedEvent {
@@ -297,11 +721,34 @@ mod payload {
     pub(super) enum UIEventPayload {
         UserMessage {
             server_id: ServerId,
-            channel: ChannelId,
+            /// None이면 서버가 보낸 메세지로 서버 버퍼에 표시
+            channel: Option<ChannelId>,
             nick: String,
             content: String,
             timestamp: u64,
         },
+        Action {
+            server_id: ServerId,
+            channel: Option<ChannelId>,
+            nick: String,
+            content: String,
+            timestamp: u64,
+        },
+        Notice {
+            server_id: ServerId,
+            channel: Option<ChannelId>,
+            nick: String,
+            content: String,
+            timestamp: u64,
+        },
+        CtcpReply {
+            server_id: ServerId,
+            nick: String,
+            command: String,
+            params: String,
+            /// 보낸 PING에 대한 응답이면 왕복 시간
+            rtt_ms: Option<u64>,
+        },
         Join {
             server_id: ServerId,
             channel: ChannelId,
@@ -313,6 +760,18 @@ mod payload {
             nick: String,
             reason: Option<String>,
         },
+        Kick {
+            server_id: ServerId,
+            channel: ChannelId,
+            nick: String,
+            by: String,
+            reason: Option<String>,
+        },
+        Invite {
+            server_id: ServerId,
+            channel: ChannelId,
+            by: String,
+        },
         Quit {
             server_id: ServerId,
             nick: String,
@@ -323,10 +782,20 @@ mod payload {
             old_nick: String,
             new_nick: String,
         },
+        /// target은 채널 또는 자신의 닉네임
+        Mode {
+            server_id: ServerId,
+            target: String,
+            setter: String,
+            changes: Vec<ModeChange>,
+        },
         Topic {
             server_id: ServerId,
             channel: ChannelId,
             topic: Option<String>,
+            set_by: Option<String>,
+            set_at: Option<u64>,
+            on_join: bool,
         },
         Error {
             server_id: ServerId,
@@ -349,4 +818,57 @@ mod payload {
             }
         }
     }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct NicknameAcquiredPayload {
+        server_id: ServerId,
+        nickname: String,
+        fallback: bool,
+    }
+
+    impl NicknameAcquiredPayload {
+        pub(super) fn new(server_id: ServerId, nickname: &str, fallback: bool) -> Self {
+            Self {
+                server_id,
+                nickname: nickname.to_string(),
+                fallback,
+            }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct NickRecoveredPayload {
+        server_id: ServerId,
+        nickname: String,
+    }
+
+    impl NickRecoveredPayload {
+        pub(super) fn new(server_id: ServerId, nickname: &str) -> Self {
+            Self {
+                server_id,
+                nickname: nickname.to_string(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct TlsUntrustedPayload {
+        server_id: ServerId,
+        /// 인증서를 제시한 주소 (`host:port`, TLS는 `host:+port`)
+        endpoint: String,
+        fingerprint: String,
+    }
+
+    impl TlsUntrustedPayload {
+        pub(super) fn new(server_id: ServerId, endpoint: String, fingerprint: &str) -> Self {
+            Self {
+                server_id,
+                endpoint,
+                fingerprint: fingerprint.to_string(),
+            }
+        }
+    }
 }
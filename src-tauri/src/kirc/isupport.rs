@@ -0,0 +1,351 @@
+use crate::kirc::casemap::CaseMapping;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// 서버가 RPL_ISUPPORT(005)로 알려준 토큰, 연결마다 초기화 (NETWORK 제외)
+#[derive(Clone, Default, Debug)]
+pub(in crate::kirc) struct ISupport {
+    tokens: BTreeMap<String, Option<String>>,
+}
+
+/// PREFIX 토큰의 모드 문자와 닉네임 앞에 붙는 기호 (`o` → `@`)
+#[derive(Clone, Copy, Serialize, Debug, PartialEq)]
+pub(in crate::kirc) struct PrefixMode {
+    pub(in crate::kirc) mode: char,
+    pub(in crate::kirc) prefix: char,
+}
+
+/// CHANMODES 토큰의 A,B,C,D 그룹
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::kirc) struct ChanModes {
+    /// 목록에 추가/제거, 항상 인자 (b, e, I)
+    pub(in crate::kirc) list: String,
+    /// 항상 인자 (k)
+    pub(in crate::kirc) always_param: String,
+    /// 설정할 때만 인자 (l)
+    pub(in crate::kirc) set_param: String,
+    /// 인자 없음 (i, m, n, t...)
+    pub(in crate::kirc) flag: String,
+}
+
+/// 프론트 전달용 ISUPPORT 요약
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServerFeatures {
+    network: Option<String>,
+    chantypes: String,
+    prefix: Vec<PrefixMode>,
+    chanmodes: ChanModes,
+    casemapping: String,
+    nicklen: Option<usize>,
+    topiclen: Option<usize>,
+    maxtargets: Option<usize>,
+    targmax: HashMap<String, Option<usize>>,
+    statusmsg: String,
+    monitor: bool,
+    monitor_limit: Option<usize>,
+    /// 해석하지 않은 토큰 포함 전체
+    tokens: BTreeMap<String, Option<String>>,
+}
+
+impl From<&ISupport> for ServerFeatures {
+    fn from(isupport: &ISupport) -> Self {
+        Self {
+            network: isupport.network().map(str::to_string),
+            chantypes: isupport.chantypes().to_string(),
+            prefix: isupport.prefix(),
+            chanmodes: isupport.chanmodes(),
+            casemapping: isupport.casemapping().to_string(),
+            nicklen: isupport.nicklen(),
+            topiclen: isupport.topiclen(),
+            maxtargets: isupport.maxtargets(),
+            targmax: isupport.targmax(),
+            statusmsg: isupport.statusmsg().to_string(),
+            monitor: isupport.monitor(),
+            monitor_limit: isupport.monitor_limit(),
+            tokens: isupport.tokens.clone(),
+        }
+    }
+}
+
+impl ISupport {
+    /// 005 인자 (`<nick> TOKEN... :are supported by this server`)를 반영
+    pub(in crate::kirc) fn apply(&mut self, args: &[String]) {
+        let Some((_, rest)) = args.split_first() else {
+            return;
+        };
+        // 마지막 인자는 사람이 읽는 설명
+        let tokens = match rest.split_last() {
+            Some((last, tokens)) if last.contains(' ') => tokens,
+            _ => rest,
+        };
+
+        for token in tokens {
+            if let Some(key) = token.strip_prefix('-') {
+                self.tokens.remove(&key.to_ascii_uppercase());
+                continue;
+            }
+
+            match token.split_once('=') {
+                Some((key, value)) => {
+                    self.tokens
+                        .insert(key.to_ascii_uppercase(), Some(unescape_value(value)));
+                }
+                None => {
+                    self.tokens.insert(token.to_ascii_uppercase(), None);
+                }
+            }
+        }
+    }
+
+    /// 새 연결 전 초기화, 005를 다시 받기 전까지 표시 이름이 주소로 바뀌지 않게 NETWORK는 유지
+    pub(in crate::kirc) fn reset(&mut self) {
+        self.tokens.retain(|key, _| key == "NETWORK");
+    }
+
+    pub(in crate::kirc) fn get(&self, key: &str) -> Option<Option<&str>> {
+        self.tokens.get(key).map(Option::as_deref)
+    }
+
+    fn value(&self, key: &str) -> Option<&str> {
+        self.get(key).flatten().filter(|v| !v.is_empty())
+    }
+
+    fn number(&self, key: &str) -> Option<usize> {
+        self.value(key).and_then(|v| v.parse().ok())
+    }
+
+    pub(in crate::kirc) fn network(&self) -> Option<&str> {
+        self.value("NETWORK")
+    }
+
+    pub(in crate::kirc) fn chantypes(&self) -> &str {
+        match self.get("CHANTYPES") {
+            Some(value) => value.unwrap_or_default(),
+            None => "#&",
+        }
+    }
+
+    pub(in crate::kirc) fn prefix(&self) -> Vec<PrefixMode> {
+        let Some(value) = self.get("PREFIX") else {
+            return vec![
+                PrefixMode {
+                    mode: 'o',
+                    prefix: '@',
+                },
+                PrefixMode {
+                    mode: 'v',
+                    prefix: '+',
+                },
+            ];
+        };
+
+        let Some((modes, prefixes)) = value
+            .and_then(|v| v.strip_prefix('('))
+            .and_then(|v| v.split_once(')'))
+        else {
+            return Vec::new();
+        };
+
+        modes
+            .chars()
+            .zip(prefixes.chars())
+            .map(|(mode, prefix)| PrefixMode { mode, prefix })
+            .collect()
+    }
+
+    pub(in crate::kirc) fn chanmodes(&self) -> ChanModes {
+        let value = self.value("CHANMODES").unwrap_or("b,k,l,imnpst");
+        let mut groups = value.split(',').map(str::to_string);
+
+        ChanModes {
+            list: groups.next().unwrap_or_default(),
+            always_param: groups.next().unwrap_or_default(),
+            set_param: groups.next().unwrap_or_default(),
+            flag: groups.next().unwrap_or_default(),
+        }
+    }
+
+    pub(in crate::kirc) fn casemapping(&self) -> &str {
+        self.value("CASEMAPPING").unwrap_or("rfc1459")
+    }
+
+    pub(in crate::kirc) fn case_mapping(&self) -> CaseMapping {
+        CaseMapping::from_token(self.casemapping())
+    }
+
+    pub(in crate::kirc) fn nicklen(&self) -> Option<usize> {
+        self.number("NICKLEN")
+    }
+
+    pub(in crate::kirc) fn topiclen(&self) -> Option<usize> {
+        self.number("TOPICLEN")
+    }
+
+    pub(in crate::kirc) fn maxtargets(&self) -> Option<usize> {
+        self.number("MAXTARGETS")
+    }
+
+    /// 명령별 최대 대상 수, 값이 없으면 제한 없음
+    pub(in crate::kirc) fn targmax(&self) -> HashMap<String, Option<usize>> {
+        self.value("TARGMAX")
+            .into_iter()
+            .flat_map(|v| v.split(','))
+            .filter_map(|entry| entry.split_once(':'))
+            .map(|(command, limit)| (command.to_ascii_uppercase(), limit.parse().ok()))
+            .collect()
+    }
+
+    pub(in crate::kirc) fn statusmsg(&self) -> &str {
+        self.value("STATUSMSG").unwrap_or_default()
+    }
+
+    /// 메세지 대상이 채널이면 STATUSMSG 접두사(`@#chan`)를 뗀 채널 이름
+    pub(in crate::kirc) fn channel_target<'a>(&self, target: &'a str) -> Option<&'a str> {
+        let statusmsg = self.statusmsg();
+        let channel = target.trim_start_matches(|c| statusmsg.contains(c));
+        channel
+            .starts_with(|c| self.chantypes().contains(c))
+            .then_some(channel)
+    }
+
+    pub(in crate::kirc) fn monitor(&self) -> bool {
+        self.get("MONITOR").is_some()
+    }
+
+    pub(in crate::kirc) fn monitor_limit(&self) -> Option<usize> {
+        self.number("MONITOR")
+    }
+}
+
+/// 토큰 값의 `\xHH` 이스케이프 해제
+fn unescape_value(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'\\' && tail.len() >= 3 && tail[0] == b'x' {
+            if let Some(byte) = std::str::from_utf8(&tail[1..3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                bytes.push(byte);
+                rest = &tail[3..];
+                continue;
+            }
+        }
+        bytes.push(b);
+        rest = tail;
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn isupport(line: &[&str]) -> ISupport {
+        let mut args: Vec<String> = vec!["kirc".to_string()];
+        args.extend(line.iter().map(ToString::to_string));
+        args.push("are supported by this server".to_string());
+
+        let mut isupport = ISupport::default();
+        isupport.apply(&args);
+        isupport
+    }
+
+    #[test]
+    fn test_reset_keeps_network() {
+        let mut isupport = isupport(&[
+            "NETWORK=Libera.Chat",
+            "CASEMAPPING=ascii",
+            "PREFIX=(qov)~@+",
+        ]);
+        isupport.reset();
+
+        assert_eq!(isupport.network(), Some("Libera.Chat"));
+        assert_eq!(isupport.casemapping(), "rfc1459");
+        assert_eq!(isupport.prefix().len(), 2);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let isupport = ISupport::default();
+
+        assert_eq!(isupport.network(), None);
+        assert_eq!(isupport.chantypes(), "#&");
+        assert_eq!(isupport.casemapping(), "rfc1459");
+        assert_eq!(isupport.prefix().len(), 2);
+        assert_eq!(isupport.chanmodes().flag, "imnpst");
+    }
+
+    #[test]
+    fn test_apply_tokens() {
+        let isupport = isupport(&[
+            "NETWORK=Libera.Chat",
+            "CHANTYPES=#",
+            "PREFIX=(qaohv)~&@%+",
+            "CHANMODES=eIbq,k,flj,CFLMPQScgimnprstuz",
+            "CASEMAPPING=ascii",
+            "NICKLEN=16",
+            "TOPICLEN=390",
+            "TARGMAX=NAMES:1,PRIVMSG:4,JOIN:",
+            "STATUSMSG=@+",
+            "MONITOR=100",
+        ]);
+
+        assert_eq!(isupport.network(), Some("Libera.Chat"));
+        assert_eq!(isupport.chantypes(), "#");
+        assert_eq!(
+            isupport.prefix()[0],
+            PrefixMode {
+                mode: 'q',
+                prefix: '~'
+            }
+        );
+        assert_eq!(isupport.prefix().len(), 5);
+        assert_eq!(isupport.chanmodes().list, "eIbq");
+        assert_eq!(isupport.chanmodes().set_param, "flj");
+        assert_eq!(isupport.casemapping(), "ascii");
+        assert_eq!(isupport.nicklen(), Some(16));
+        assert_eq!(isupport.topiclen(), Some(390));
+        assert_eq!(isupport.targmax().get("PRIVMSG"), Some(&Some(4)));
+        assert_eq!(isupport.targmax().get("JOIN"), Some(&None));
+        assert_eq!(isupport.statusmsg(), "@+");
+        assert!(isupport.monitor());
+        assert_eq!(isupport.monitor_limit(), Some(100));
+    }
+
+    #[test]
+    fn test_negated_token() {
+        let mut isupport = isupport(&["NETWORK=Example", "MONITOR"]);
+        isupport.apply(&["kirc".to_string(), "-MONITOR".to_string()]);
+
+        assert!(!isupport.monitor());
+        assert_eq!(isupport.network(), Some("Example"));
+    }
+
+    #[test]
+    fn test_escaped_value() {
+        let isupport = isupport(&["NETWORK=Example\\x20Net"]);
+        assert_eq!(isupport.network(), Some("Example Net"));
+    }
+
+    #[test]
+    fn test_channel_target() {
+        let isupport = isupport(&["CHANTYPES=#", "STATUSMSG=@+"]);
+
+        assert_eq!(isupport.channel_target("#rust"), Some("#rust"));
+        assert_eq!(isupport.channel_target("@#rust"), Some("#rust"));
+        assert_eq!(isupport.channel_target("&local"), None);
+        assert_eq!(isupport.channel_target("kirc"), None);
+        assert_eq!(isupport.channel_target("*"), None);
+    }
+
+    #[test]
+    fn test_empty_prefix() {
+        assert!(isupport(&["PREFIX="]).prefix().is_empty());
+    }
+}
@@ -1,14 +1,24 @@
-use crate::kirc::core::server_actor;
-use crate::kirc::emits::{emit_server_added, emit_server_status};
+use crate::kirc::core::{server_actor, ActorExit};
+use crate::kirc::emits::{
+    emit_server_added, emit_server_removed, emit_server_status, emit_server_updated,
+    emit_system_message,
+};
 use crate::kirc::state::app::AppState;
 use crate::kirc::state::kirc::KircState;
+use crate::kirc::tls::normalize_fingerprint;
 use crate::kirc::types::server::ServerConfig;
 use crate::kirc::types::{ServerCommand, ServerId, ServerStatus};
 use anyhow::{anyhow, Context};
 use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::AppHandle;
-use tracing::{debug, info};
+use tracing::{debug, info, instrument, warn};
+
+/// 첫 재연결 대기 시간
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(2);
+/// 재연결 대기 시간 상한
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(300);
 
 pub(crate) struct KircManager {
     kirc_state: Arc<KircState>,
@@ -45,10 +55,12 @@ impl KircManager {
 
     fn run_server(&self, server_id: ServerId) -> anyhow::Result<()> {
         if let Some(server) = self.kirc_state.get_server(server_id) {
-            let config = server.config();
-
             info!(server_id = %server_id, "Spawn server actor");
-            let handle = tokio::spawn(server_actor(server_id, config, self.app_handle.clone()));
+            let handle = tokio::spawn(Self::supervise(
+                server_id,
+                self.kirc_state.clone(),
+                self.app_handle.clone(),
+            ));
 
             server.transition_to_connecting(handle);
             Ok(())
@@ -56,6 +68,100 @@ impl KircManager {
             Err(anyhow!("Server not found"))
         }
     }
+
+    /// server actor를 실행하고, 예기치 않게 끊기면 backoff 후 재연결
+    ///
+    /// 사용자 QUIT이나 최초 연결 실패는 재연결하지 않음
+    #[instrument(name = "supervisor", skip_all, fields(server_id = %server_id))]
+    async fn supervise(server_id: ServerId, kirc_state: Arc<KircState>, app_handle: AppHandle) {
+        let mut attempt: u32 = 0;
+        // 다음 연결을 시작할 주소, 처음에는 기본 주소부터 순서대로
+        let mut endpoint_cursor: usize = 0;
+
+        loop {
+            // shutdown 중 drain 되었다면 종료
+            let Some(server) = kirc_state.get_server(server_id) else {
+                return;
+            };
+
+            let config = server.config();
+            let exit = server_actor(
+                server_id,
+                config.clone(),
+                endpoint_cursor,
+                app_handle.clone(),
+            )
+            .await;
+            let was_connected = matches!(server.status(), ServerStatus::Connected);
+
+            // 재연결은 마지막으로 연결된 주소의 다음 주소부터 시도 (round-robin)
+            if let Some(index) = server.connected_endpoint().and_then(|connected| {
+                config
+                    .endpoints()
+                    .iter()
+                    .position(|endpoint| *endpoint == connected)
+            }) {
+                endpoint_cursor = index + 1;
+            }
+
+            let error = match exit {
+                ActorExit::Quit => {
+                    if server.transition_to_disconnected() {
+                        let _ = emit_server_status(&app_handle, server_id, server.status());
+                    }
+                    return;
+                }
+                ActorExit::Failed(error) if attempt == 0 => {
+                    server.transition_to_failed(error);
+                    let _ = emit_server_status(&app_handle, server_id, server.status());
+                    return;
+                }
+                ActorExit::Failed(error) | ActorExit::ConnectionLost(error) => error,
+            };
+
+            // 등록까지 성공했던 연결이면 backoff를 처음부터 다시 시작
+            attempt = if was_connected { 1 } else { attempt + 1 };
+            let delay = reconnect_delay(attempt, rand::random());
+            let next_retry_at =
+                chrono::Utc::now().timestamp_millis() as u64 + delay.as_millis() as u64;
+
+            if !server.transition_to_reconnecting(attempt, next_retry_at) {
+                return;
+            }
+
+            warn!(attempt, delay_secs = delay.as_secs(), error = %error, "Connection lost, reconnecting");
+            let _ = emit_server_status(&app_handle, server_id, server.status());
+            let _ = emit_system_message(
+                &app_handle,
+                server_id,
+                &format!(
+                    "연결이 끊어졌습니다: {error} ({}초 후 재연결 시도 #{attempt})",
+                    delay.as_secs()
+                ),
+            );
+            drop(server);
+
+            tokio::time::sleep(delay).await;
+
+            let Some(server) = kirc_state.get_server(server_id) else {
+                return;
+            };
+            if !server.resume_connecting() {
+                return;
+            }
+            let _ = emit_server_status(&app_handle, server_id, ServerStatus::Connecting);
+        }
+    }
+}
+
+/// 지수 backoff에 jitter 적용 (equal jitter). jitter는 0.0..1.0
+fn reconnect_delay(attempt: u32, jitter: f64) -> Duration {
+    let exp = RECONNECT_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RECONNECT_MAX_DELAY);
+    let half = exp / 2;
+
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
 }
 
 // pub(in crate::kirc)
@@ -80,6 +186,7 @@ impl KircManager {
             }
         } else {
             // New server
+            let config = config.validated()?;
             let sid = self.kirc_state.add_server(config.clone())?;
             emit_server_added(
                 &self.app_handle,
@@ -112,7 +219,7 @@ impl KircManager {
     pub(in crate::kirc) fn cancel_connect(&self, server_id: ServerId) -> anyhow::Result<()> {
         if let Some(server) = self.kirc_state.get_server(server_id) {
             if server.abort_connecting() {
-                emit_server_status(&self.app_handle, server_id, ServerStatus::Failed)?;
+                emit_server_status(&self.app_handle, server_id, server.status())?;
             }
             Ok(())
         } else {
@@ -120,11 +227,115 @@ impl KircManager {
         }
     }
 
+    /// 실행 중이면 QUIT으로 종료한 뒤 서버를 목록과 설정 파일에서 제거
+    pub(in crate::kirc) async fn remove_server(&self, server_id: ServerId) -> anyhow::Result<()> {
+        let server = self
+            .kirc_state
+            .remove_server(server_id)
+            .context("Server not found")?;
+
+        info!(server_id = %server_id, "Remove server");
+        server.take_runtime().graceful_shutdown().await;
+        emit_server_removed(&self.app_handle, server_id)?;
+        self.kirc_state.save_snapshot()?;
+
+        Ok(())
+    }
+
+    /// 서버 설정을 변경해 저장, reconnect면 새 설정으로 다시 연결
+    pub(in crate::kirc) async fn update_server_config(
+        &self,
+        server_id: ServerId,
+        update: impl FnOnce(ServerConfig) -> ServerConfig,
+        reconnect: bool,
+    ) -> anyhow::Result<()> {
+        let server = self
+            .kirc_state
+            .get_server(server_id)
+            .context("Server not found")?;
+
+        let config = update(server.config()).validated()?;
+        server.set_config(config.clone());
+        self.kirc_state.save_snapshot()?;
+
+        emit_server_updated(
+            &self.app_handle,
+            server_id,
+            config.server(),
+            config.port(),
+            config.use_tls(),
+            config.nickname(),
+            server.status(),
+        )?;
+
+        if reconnect {
+            if self.is_shutting_down() {
+                return Err(anyhow!("Application is shutting down"));
+            }
+
+            server.take_runtime().graceful_shutdown().await;
+            self.run_server(server_id)?;
+            emit_server_status(&self.app_handle, server_id, ServerStatus::Connecting)?;
+        }
+
+        Ok(())
+    }
+
+    /// TOFU로 제시된 서버 인증서를 승인하고 fingerprint를 저장
+    pub(in crate::kirc) fn accept_tls_certificate(
+        &self,
+        server_id: ServerId,
+        fingerprint: &str,
+    ) -> anyhow::Result<()> {
+        let server = self
+            .kirc_state
+            .get_server(server_id)
+            .context("Can't find server")?;
+        let (endpoint, pending, tx) = server
+            .take_tls_approval()
+            .context("No certificate is waiting for approval")?;
+
+        if pending != normalize_fingerprint(fingerprint) {
+            let _ = tx.send(false);
+            return Err(anyhow!("Certificate fingerprint does not match"));
+        }
+
+        server.pin_tls_fingerprint(&endpoint, &pending);
+        self.kirc_state.save_snapshot()?;
+        let _ = tx.send(true);
+
+        Ok(())
+    }
+
+    pub(in crate::kirc) fn reject_tls_certificate(
+        &self,
+        server_id: ServerId,
+    ) -> anyhow::Result<()> {
+        let server = self
+            .kirc_state
+            .get_server(server_id)
+            .context("Can't find server")?;
+        let (_, _, tx) = server
+            .take_tls_approval()
+            .context("No certificate is waiting for approval")?;
+        let _ = tx.send(false);
+
+        Ok(())
+    }
+
     pub(in crate::kirc) fn process_auto_connect(&self) {
-        let server_ids: Vec<ServerId> = self.kirc_state.get_all_servers().keys().cloned().collect();
+        let server_ids: Vec<ServerId> = self
+            .kirc_state
+            .get_all_servers()
+            .into_iter()
+            .filter(|(_, server)| server.should_auto_connect())
+            .map(|(server_id, _)| server_id)
+            .collect();
         debug!(server_id = ?server_ids, "Auto-connecting servers");
         for server_id in server_ids {
-            let _ = self.run_server(server_id);
+            if self.run_server(server_id).is_ok() {
+                let _ = emit_server_status(&self.app_handle, server_id, ServerStatus::Connecting);
+            }
         }
     }
 
@@ -190,3 +401,27 @@ impl KircManager {
         self.complete_shutdown();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_grows_exponentially() {
+        assert_eq!(reconnect_delay(1, 1.0), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(2, 1.0), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(3, 1.0), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_reconnect_delay_jitter() {
+        assert_eq!(reconnect_delay(3, 0.0), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(3, 0.5), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn test_reconnect_delay_is_capped() {
+        assert_eq!(reconnect_delay(20, 1.0), RECONNECT_MAX_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX, 1.0), RECONNECT_MAX_DELAY);
+    }
+}
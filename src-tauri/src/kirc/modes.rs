@@ -0,0 +1,238 @@
+use crate::kirc::isupport::{ChanModes, PrefixMode};
+use irc::proto::{Mode, UserMode};
+use serde::Serialize;
+
+/// MODE 한 글자의 변경
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(in crate::kirc) struct ModeChange {
+    pub(in crate::kirc) set: bool,
+    pub(in crate::kirc) mode: char,
+    pub(in crate::kirc) arg: Option<String>,
+}
+
+/// CHANMODES, PREFIX 기준으로 채널 모드 종류 구분
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(in crate::kirc) enum ChannelModeKind {
+    /// A: 목록 (b, e, I), 채널 상태에는 저장하지 않음
+    List,
+    /// PREFIX: 멤버 권한 (o, v...)
+    Member,
+    /// B: 항상 인자 (k)
+    AlwaysParam,
+    /// C: 설정할 때만 인자 (l)
+    SetParam,
+    /// D 또는 알 수 없는 모드: 인자 없음
+    Flag,
+}
+
+impl ChannelModeKind {
+    pub(in crate::kirc) fn of(mode: char, chanmodes: &ChanModes, prefix: &[PrefixMode]) -> Self {
+        if prefix.iter().any(|p| p.mode == mode) {
+            ChannelModeKind::Member
+        } else if chanmodes.list.contains(mode) {
+            ChannelModeKind::List
+        } else if chanmodes.always_param.contains(mode) {
+            ChannelModeKind::AlwaysParam
+        } else if chanmodes.set_param.contains(mode) {
+            ChannelModeKind::SetParam
+        } else {
+            ChannelModeKind::Flag
+        }
+    }
+
+    fn takes_arg(self, set: bool) -> bool {
+        match self {
+            ChannelModeKind::List | ChannelModeKind::Member | ChannelModeKind::AlwaysParam => true,
+            ChannelModeKind::SetParam => set,
+            ChannelModeKind::Flag => false,
+        }
+    }
+}
+
+/// `+nt-k+l key 10` 형태의 채널 모드 파싱
+pub(in crate::kirc) fn parse_channel_modes(
+    modestring: &str,
+    args: &[String],
+    chanmodes: &ChanModes,
+    prefix: &[PrefixMode],
+) -> Vec<ModeChange> {
+    let mut args = args.iter();
+    parse_modes(modestring, |mode, set| {
+        if ChannelModeKind::of(mode, chanmodes, prefix).takes_arg(set) {
+            args.next().cloned()
+        } else {
+            None
+        }
+    })
+}
+
+/// 유저 모드는 인자 없음
+pub(in crate::kirc) fn parse_user_modes(modestring: &str) -> Vec<ModeChange> {
+    parse_modes(modestring, |_, _| None)
+}
+
+fn parse_modes(
+    modestring: &str,
+    mut arg: impl FnMut(char, bool) -> Option<String>,
+) -> Vec<ModeChange> {
+    let mut set = true;
+    let mut changes = Vec::new();
+
+    for c in modestring.chars() {
+        match c {
+            '+' => set = true,
+            '-' => set = false,
+            mode => changes.push(ModeChange {
+                set,
+                mode,
+                arg: arg(mode, set),
+            }),
+        }
+    }
+
+    changes
+}
+
+/// irc-proto가 파싱한 유저 MODE를 modestring으로 되돌림
+///
+/// 채널 MODE는 KircCodec이 원본 인자 그대로 넘기므로 유저 모드에만 사용, 유저 모드는 인자 없음
+pub(in crate::kirc) fn unparse_user_modes(modes: &[Mode<UserMode>]) -> String {
+    let mut modestring = String::new();
+    let mut current = None;
+
+    for mode in modes {
+        let (sign, mode) = match mode {
+            Mode::Plus(mode, _) => ('+', mode),
+            Mode::Minus(mode, _) => ('-', mode),
+            Mode::NoPrefix(mode) => ('+', mode),
+        };
+
+        if current != Some(sign) {
+            modestring.push(sign);
+            current = Some(sign);
+        }
+        modestring.push_str(&mode.to_string());
+    }
+
+    modestring
+}
+
+/// 현재 채널 모드를 `+ntl 10` 형태로 표시
+pub(in crate::kirc) fn format_modes<'a>(
+    modes: impl IntoIterator<Item = (&'a char, &'a Option<String>)>,
+) -> String {
+    let mut flags = String::from("+");
+    let mut args = Vec::new();
+    for (mode, arg) in modes {
+        flags.push(*mode);
+        args.extend(arg.as_deref());
+    }
+
+    if flags.len() == 1 {
+        return String::new();
+    }
+
+    std::iter::once(flags.as_str())
+        .chain(args)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irc::proto::{Command, Message};
+    use std::collections::BTreeMap;
+
+    fn chanmodes() -> ChanModes {
+        ChanModes {
+            list: "beI".to_string(),
+            always_param: "k".to_string(),
+            set_param: "lj".to_string(),
+            flag: "imnst".to_string(),
+        }
+    }
+
+    fn prefix() -> Vec<PrefixMode> {
+        "ov".chars()
+            .zip("@+".chars())
+            .map(|(mode, prefix)| PrefixMode { mode, prefix })
+            .collect()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn change(set: bool, mode: char, arg: Option<&str>) -> ModeChange {
+        ModeChange {
+            set,
+            mode,
+            arg: arg.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_channel_modes() {
+        let changes = parse_channel_modes(
+            "+ntk-l+ob",
+            &args(&["secret", "alice", "*!*@spam"]),
+            &chanmodes(),
+            &prefix(),
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                change(true, 'n', None),
+                change(true, 't', None),
+                change(true, 'k', Some("secret")),
+                change(false, 'l', None),
+                change(true, 'o', Some("alice")),
+                change(true, 'b', Some("*!*@spam")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_server_specific_param_mode() {
+        // j는 irc-proto가 모르는 모드지만 CHANMODES C 그룹
+        let changes = parse_channel_modes("+jv", &args(&["3:5", "bob"]), &chanmodes(), &prefix());
+        assert_eq!(
+            changes,
+            vec![
+                change(true, 'j', Some("3:5")),
+                change(true, 'v', Some("bob"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_user_modes() {
+        assert_eq!(
+            parse_user_modes("+iw-x"),
+            vec![
+                change(true, 'i', None),
+                change(true, 'w', None),
+                change(false, 'x', None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unparse_user_modes() {
+        let message: Message = ":kirc MODE kirc +iw-x".parse().unwrap();
+        let Command::UserMODE(_, modes) = message.command else {
+            panic!("not a user mode");
+        };
+
+        assert_eq!(unparse_user_modes(&modes), "+iw-x");
+    }
+
+    #[test]
+    fn test_format_modes() {
+        let modes = BTreeMap::from([('l', Some("10".to_string())), ('n', None), ('t', None)]);
+        assert_eq!(format_modes(&modes), "+lnt 10");
+        assert_eq!(format_modes(&BTreeMap::new()), "");
+    }
+}
@@ -2,26 +2,55 @@ use crate::kirc::state::channel::ChannelState;
 use crate::kirc::state::kirc::KircState;
 use crate::kirc::state::server::ServerState;
 use crate::kirc::types::server::ServerConfig;
-use crate::kirc::types::ChannelId;
+use crate::kirc::types::{ChannelId, ServerId};
 use crate::memento::Memento;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 pub(super) struct ServerStateSnapshot {
+    /// 이전 버전 설정 파일에는 없음, 불러올 때 새로 발급
+    #[serde(default)]
+    id: Option<ServerId>,
     config: ServerConfig,
     channels: HashMap<ChannelId, ChannelState>,
+    /// 열려 있던 개인 대화 상대 닉네임
+    #[serde(default)]
+    queries: Vec<String>,
+    /// 저장 시점에 연결(또는 연결 시도) 중이었는지
+    #[serde(default)]
+    connected: bool,
 }
 
 impl ServerStateSnapshot {
-    pub(super) fn new(config: ServerConfig, channels: HashMap<ChannelId, ChannelState>) -> Self {
-        Self { config, channels }
+    pub(super) fn new(
+        config: ServerConfig,
+        channels: HashMap<ChannelId, ChannelState>,
+        queries: Vec<String>,
+        connected: bool,
+    ) -> Self {
+        Self {
+            id: None,
+            config,
+            channels,
+            queries,
+            connected,
+        }
+    }
+
+    pub(super) fn with_id(mut self, id: ServerId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub(super) fn id(&self) -> Option<ServerId> {
+        self.id
     }
 }
 
 impl Memento<ServerState> for ServerStateSnapshot {
     fn restore(self) -> ServerState {
-        ServerState::with_channel(self.config, self.channels)
+        ServerState::with_channel(self.config, self.channels, self.queries, self.connected)
     }
 }
 
@@ -30,6 +59,13 @@ pub(crate) struct KircStateSnapshot {
     servers: Vec<ServerStateSnapshot>,
 }
 
+impl KircStateSnapshot {
+    /// ServerId가 없는 이전 버전 설정 파일인지 (복원 후 다시 저장해 id 고정 필요)
+    pub(crate) fn needs_migration(&self) -> bool {
+        self.servers.iter().any(|server| server.id.is_none())
+    }
+}
+
 impl From<Vec<ServerStateSnapshot>> for KircStateSnapshot {
     fn from(value: Vec<ServerStateSnapshot>) -> Self {
         Self { servers: value }
@@ -49,3 +85,79 @@ impl Memento<KircState> for KircStateSnapshot {
         KircState::from_iter(self.servers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kirc::types::server::AutoConnect;
+    use crate::memento::Originator;
+
+    const LEGACY_SNAPSHOT: &str = r#"{"servers":[{"config":{"server":"irc.example.com","port":6697,"use_tls":true,"nickname":"kirc"},"channels":{}}]}"#;
+
+    #[test]
+    fn test_legacy_snapshot_needs_migration() {
+        let snapshot: KircStateSnapshot = serde_json::from_str(LEGACY_SNAPSHOT).unwrap();
+        assert!(snapshot.needs_migration());
+
+        let state = snapshot.restore();
+        assert_eq!(state.get_all_servers().len(), 1);
+        assert!(!state.snapshot().needs_migration());
+    }
+
+    #[test]
+    fn test_server_id_survives_restore() {
+        let snapshot: KircStateSnapshot = serde_json::from_str(LEGACY_SNAPSHOT).unwrap();
+        let state = snapshot.restore();
+        let ids: Vec<ServerId> = state.get_all_servers().into_keys().collect();
+
+        let json = serde_json::to_string(&state.snapshot()).unwrap();
+        let restored = serde_json::from_str::<KircStateSnapshot>(&json)
+            .unwrap()
+            .restore();
+        let restored_ids: Vec<ServerId> = restored.get_all_servers().into_keys().collect();
+
+        assert_eq!(ids, restored_ids);
+    }
+
+    #[test]
+    fn test_queries_survive_restore() {
+        let snapshot: KircStateSnapshot = serde_json::from_str(LEGACY_SNAPSHOT).unwrap();
+        let config = snapshot.servers[0].config.clone();
+        let server =
+            ServerState::with_channel(config, HashMap::new(), vec!["Alice".to_string()], false);
+        assert_eq!(server.open_query("alice"), ("Alice".to_string(), false));
+        assert_eq!(server.open_query("bob"), ("bob".to_string(), true));
+        assert_eq!(server.rename_query("BOB", "Bobby"), Some("bob".to_string()));
+
+        let json = serde_json::to_string(&server.snapshot()).unwrap();
+        let restored = serde_json::from_str::<ServerStateSnapshot>(&json)
+            .unwrap()
+            .restore();
+        assert_eq!(restored.queries(), vec!["Alice", "Bobby"]);
+        assert_eq!(restored.close_query("ALICE"), Some("Alice".to_string()));
+    }
+
+    #[test]
+    fn test_auto_connect_policy() {
+        let snapshot: KircStateSnapshot = serde_json::from_str(LEGACY_SNAPSHOT).unwrap();
+        let server = snapshot.servers.into_iter().next().unwrap().restore();
+        // 이전 설정 파일은 기존처럼 항상 자동 연결
+        assert!(server.should_auto_connect());
+
+        let config = server.config().with_auto_connect(AutoConnect::LastSession);
+        assert!(
+            !ServerState::with_channel(config.clone(), HashMap::new(), Vec::new(), false)
+                .should_auto_connect()
+        );
+        assert!(
+            ServerState::with_channel(config.clone(), HashMap::new(), Vec::new(), true)
+                .should_auto_connect()
+        );
+
+        let config = config.with_auto_connect(AutoConnect::Never);
+        assert!(
+            !ServerState::with_channel(config, HashMap::new(), Vec::new(), true)
+                .should_auto_connect()
+        );
+    }
+}
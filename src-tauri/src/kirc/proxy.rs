@@ -0,0 +1,207 @@
+use crate::kirc::types::server::{ProxyConfig, ProxyKind};
+use anyhow::{anyhow, bail};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+use tracing::info;
+
+/// CONNECT 응답 헤더 최대 길이
+const MAX_HTTP_RESPONSE_LEN: usize = 8192;
+
+/// 프록시를 거쳐 host:port로 터널링된 TCP 스트림 반환
+///
+/// 호스트 이름은 프록시 쪽에서 resolve (Tor 등)
+pub(super) async fn connect_via(
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<TcpStream> {
+    info!(event = "proxy_connect", kind = ?proxy.kind(), proxy = %proxy.host(), "Connecting through proxy");
+
+    match proxy.kind() {
+        ProxyKind::Socks5 => socks5_connect(proxy, host, port).await,
+        ProxyKind::Http => http_connect(proxy, host, port).await,
+    }
+}
+
+async fn socks5_connect(proxy: &ProxyConfig, host: &str, port: u16) -> anyhow::Result<TcpStream> {
+    let proxy_addr = (proxy.host(), proxy.port());
+    let stream = match proxy.credentials() {
+        Some((username, password)) => {
+            Socks5Stream::connect_with_password(proxy_addr, (host, port), username, password)
+                .await?
+        }
+        None => Socks5Stream::connect(proxy_addr, (host, port)).await?,
+    };
+
+    Ok(stream.into_inner())
+}
+
+async fn http_connect(proxy: &ProxyConfig, host: &str, port: u16) -> anyhow::Result<TcpStream> {
+    let mut stream = TcpStream::connect((proxy.host(), proxy.port())).await?;
+
+    let authority = authority(host, port);
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some((username, password)) = proxy.credentials() {
+        let token = BASE64_STANDARD.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // 터널이 열린 뒤 서버가 바로 보내는 데이터를 먹지 않도록 헤더 끝까지 한 바이트씩 읽음
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE_LEN {
+            bail!("Proxy response too long");
+        }
+        let mut byte = [0u8; 1];
+        if stream.read(&mut byte).await? == 0 {
+            bail!("Proxy closed connection");
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    if is_success_status(status_line) {
+        Ok(stream)
+    } else {
+        Err(anyhow!("Proxy refused CONNECT: {status_line}"))
+    }
+}
+
+/// CONNECT 대상, IPv6 주소는 대괄호로 감쌈
+fn authority(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+/// `HTTP/1.1 2xx ...` 상태줄이면 터널이 열린 것
+fn is_success_status(status_line: &str) -> bool {
+    status_line.split_whitespace().nth(1).is_some_and(|code| {
+        code.len() == 3 && code.starts_with('2') && code.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn proxy(kind: &str, port: u16) -> ProxyConfig {
+        serde_json::from_value(serde_json::json!({
+            "type": kind,
+            "host": "127.0.0.1",
+            "port": port,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_authority_brackets_ipv6() {
+        assert_eq!(authority("irc.example.com", 6667), "irc.example.com:6667");
+        assert_eq!(authority("::1", 6667), "[::1]:6667");
+    }
+
+    #[test]
+    fn test_is_success_status() {
+        assert!(is_success_status("HTTP/1.1 200 Connection established"));
+        assert!(is_success_status("HTTP/1.0 204 No Content"));
+        assert!(!is_success_status(
+            "HTTP/1.1 407 Proxy Authentication Required"
+        ));
+        assert!(!is_success_status("HTTP/1.1 2000 Bogus"));
+        assert!(!is_success_status(""));
+    }
+
+    #[tokio::test]
+    async fn test_http_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n:irc NOTICE * :hi\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let mut stream = connect_via(&proxy("http", port), "irc.example.com", 6667)
+            .await
+            .unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT irc.example.com:6667 HTTP/1.1\r\n"));
+
+        // 헤더 뒤의 데이터는 그대로 남아 있어야 함
+        let mut rest = String::new();
+        stream.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, ":irc NOTICE * :hi\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_http_connect_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let result = connect_via(&proxy("http", port), "irc.example.com", 6667).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_socks5_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // 인증 없는 SOCKS5 stand-in, 요청한 도메인을 돌려줌
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            socket.read_exact(&mut greeting).await.unwrap();
+            socket.write_all(&[0x05, 0x00]).await.unwrap();
+
+            let mut header = [0u8; 5];
+            socket.read_exact(&mut header).await.unwrap();
+            assert_eq!(&header[..4], &[0x05, 0x01, 0x00, 0x03]);
+            let mut domain = vec![0u8; header[4] as usize + 2];
+            socket.read_exact(&mut domain).await.unwrap();
+
+            socket
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x1a, 0x0b])
+                .await
+                .unwrap();
+            socket.write_all(b"hello").await.unwrap();
+
+            let port = u16::from_be_bytes([domain[domain.len() - 2], domain[domain.len() - 1]]);
+            domain.truncate(domain.len() - 2);
+            (String::from_utf8(domain).unwrap(), port)
+        });
+
+        let mut stream = connect_via(&proxy("socks5", port), "irc.example.com", 6697)
+            .await
+            .unwrap();
+        assert_eq!(server.await.unwrap(), ("irc.example.com".to_string(), 6697));
+
+        let mut rest = String::new();
+        stream.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "hello");
+    }
+}
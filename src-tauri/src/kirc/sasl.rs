@@ -0,0 +1,84 @@
+use base64::prelude::{Engine, BASE64_STANDARD};
+
+/// AUTHENTICATE 한 줄에 담을 수 있는 최대 payload 길이
+const AUTHENTICATE_CHUNK_SIZE: usize = 400;
+
+/// SASL 진행 상태, 연결마다 초기화
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub(in crate::kirc) enum SaslProgress {
+    #[default]
+    Idle,
+    /// `CAP REQ :sasl` 전송
+    Requested,
+    /// `AUTHENTICATE <mechanism>` 전송
+    Authenticating,
+    Done,
+}
+
+/// PLAIN 메커니즘 payload (`authzid\0authcid\0password`)
+pub(super) fn plain_payload(account: &str, password: &str) -> String {
+    BASE64_STANDARD.encode(format!("{account}\0{account}\0{password}"))
+}
+
+/// payload를 AUTHENTICATE 줄 단위로 분할
+///
+/// 마지막 조각이 정확히 400 바이트면 끝을 알리기 위해 `+`를 추가로 보냄
+pub(super) fn authenticate_chunks(payload: &str) -> Vec<String> {
+    if payload.is_empty() {
+        return vec!["+".to_string()];
+    }
+
+    // base64 payload는 ASCII라 바이트 단위로 잘라도 안전
+    let mut chunks: Vec<String> = payload
+        .as_bytes()
+        .chunks(AUTHENTICATE_CHUNK_SIZE)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect();
+
+    if payload.len().is_multiple_of(AUTHENTICATE_CHUNK_SIZE) {
+        chunks.push("+".to_string());
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_payload() {
+        // "jilles\0jilles\0sesame"
+        assert_eq!(
+            plain_payload("jilles", "sesame"),
+            "amlsbGVzAGppbGxlcwBzZXNhbWU="
+        );
+    }
+
+    #[test]
+    fn test_authenticate_chunks_short() {
+        assert_eq!(authenticate_chunks("abcd"), vec!["abcd".to_string()]);
+    }
+
+    #[test]
+    fn test_authenticate_chunks_empty() {
+        assert_eq!(authenticate_chunks(""), vec!["+".to_string()]);
+    }
+
+    #[test]
+    fn test_authenticate_chunks_exact_boundary() {
+        let payload = "a".repeat(AUTHENTICATE_CHUNK_SIZE);
+        let chunks = authenticate_chunks(&payload);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), AUTHENTICATE_CHUNK_SIZE);
+        assert_eq!(chunks[1], "+");
+    }
+
+    #[test]
+    fn test_authenticate_chunks_split() {
+        let payload = "a".repeat(AUTHENTICATE_CHUNK_SIZE + 10);
+        let chunks = authenticate_chunks(&payload);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].len(), 10);
+    }
+}
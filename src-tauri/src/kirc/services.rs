@@ -0,0 +1,212 @@
+use crate::kirc::types::server::NickRecoveryMethod;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// NickServ 응답(NICK echo)을 기다리는 최대 시간
+pub(super) const NICK_RECOVERY_TIMEOUT: Duration = Duration::from_secs(30);
+/// GHOST 성공 응답을 알아보지 못해도 이 시간 뒤에는 직접 NICK 전송
+pub(super) const GHOST_NICK_DELAY: Duration = Duration::from_secs(5);
+
+/// 회수 명령이 성공했음을 뜻하는 NickServ 응답 (Atheme, Anope)
+const SUCCESS_REPLIES: &[&str] = &[
+    "has been ghosted",
+    "has been regained",
+    "has been killed",
+    "regained control",
+];
+const FAILURE_REPLIES: &[&str] = &[
+    "invalid password",
+    "password incorrect",
+    "access denied",
+    "not authorized",
+    "you may not",
+    "is not registered",
+    "isn't registered",
+    "is not online",
+    "isn't online",
+    "is not in use",
+    "unknown command",
+    "insufficient parameters",
+];
+
+/// 비밀번호를 인자로 받는 NickServ 명령
+const CREDENTIAL_COMMANDS: &[&str] = &["IDENTIFY", "REGAIN", "GHOST", "RECOVER", "RELEASE"];
+
+/// 기본 닉네임 회수 진행 상태, 연결마다 초기화
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(in crate::kirc) struct NickRecovery {
+    method: NickRecoveryMethod,
+    started_at: Instant,
+    /// GHOST 후 기본 닉네임으로 NICK을 보냈는지
+    nick_sent: bool,
+}
+
+/// 회수 명령에 대한 NickServ 응답
+#[derive(Debug, PartialEq)]
+pub(super) enum RecoveryReply {
+    Succeeded,
+    /// 서식을 제거한 NickServ 응답을 실패 사유로 사용
+    Failed(String),
+}
+
+impl NickRecovery {
+    pub(in crate::kirc) fn new(method: NickRecoveryMethod) -> Self {
+        Self {
+            method,
+            started_at: Instant::now(),
+            nick_sent: false,
+        }
+    }
+
+    pub(in crate::kirc) fn is_expired(&self) -> bool {
+        self.started_at.elapsed() >= NICK_RECOVERY_TIMEOUT
+    }
+
+    /// GHOST는 세션만 끊어주므로 아직 보내지 않았다면 직접 NICK을 보내야 함
+    pub(in crate::kirc) fn needs_nick(&self) -> bool {
+        self.method == NickRecoveryMethod::Ghost && !self.nick_sent
+    }
+
+    pub(in crate::kirc) fn with_nick_sent(self) -> Self {
+        Self {
+            nick_sent: true,
+            ..self
+        }
+    }
+}
+
+/// NickServ NOTICE가 회수 명령의 성공/실패 응답인지 판단
+pub(super) fn parse_recovery_reply(text: &str) -> Option<RecoveryReply> {
+    let text = strip_formatting(text);
+    let lower = text.to_lowercase();
+
+    if SUCCESS_REPLIES.iter().any(|reply| lower.contains(reply)) {
+        Some(RecoveryReply::Succeeded)
+    } else if FAILURE_REPLIES.iter().any(|reply| lower.contains(reply)) {
+        Some(RecoveryReply::Failed(text))
+    } else {
+        None
+    }
+}
+
+/// 굵게, 색상 등 mIRC 서식 코드 제거
+fn strip_formatting(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x02' | '\x0f' | '\x11' | '\x16' | '\x1d' | '\x1e' | '\x1f' => {}
+            // \x03<fg>[,<bg>], 각 색상은 최대 두 자리
+            '\x03' => {
+                for _ in 0..2 {
+                    chars.next_if(char::is_ascii_digit);
+                }
+                if chars.peek() == Some(&',') {
+                    let mut lookahead = chars.clone();
+                    lookahead.next();
+                    if lookahead.peek().is_some_and(char::is_ascii_digit) {
+                        chars.next();
+                        for _ in 0..2 {
+                            chars.next_if(char::is_ascii_digit);
+                        }
+                    }
+                }
+            }
+            c => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+/// NickServ에 보낼 회수 명령 (`REGAIN <nick> [password]`)
+pub(super) fn recovery_message(
+    method: NickRecoveryMethod,
+    nickname: &str,
+    password: Option<&str>,
+) -> String {
+    let command = match method {
+        NickRecoveryMethod::Regain => "REGAIN",
+        NickRecoveryMethod::Ghost => "GHOST",
+        NickRecoveryMethod::Recover => "RECOVER",
+    };
+
+    match password {
+        Some(password) if !password.is_empty() => format!("{command} {nickname} {password}"),
+        _ => format!("{command} {nickname}"),
+    }
+}
+
+/// 비밀번호가 들어있을 수 있는 NickServ 명령, echo로 돌아와도 화면에 표시하지 않음
+pub(super) fn is_credential_command(text: &str) -> bool {
+    text.split_whitespace().next().is_some_and(|command| {
+        CREDENTIAL_COMMANDS
+            .iter()
+            .any(|credential| command.eq_ignore_ascii_case(credential))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_credential_command() {
+        assert!(is_credential_command("REGAIN kirc secret"));
+        assert!(is_credential_command("identify secret"));
+        assert!(!is_credential_command("INFO kirc"));
+        assert!(!is_credential_command(""));
+    }
+
+    #[test]
+    fn test_recovery_message() {
+        assert_eq!(
+            recovery_message(NickRecoveryMethod::Regain, "kirc", None),
+            "REGAIN kirc"
+        );
+        assert_eq!(
+            recovery_message(NickRecoveryMethod::Ghost, "kirc", Some("secret")),
+            "GHOST kirc secret"
+        );
+        assert_eq!(
+            recovery_message(NickRecoveryMethod::Recover, "kirc", Some("")),
+            "RECOVER kirc"
+        );
+    }
+
+    #[test]
+    fn test_parse_recovery_reply_success() {
+        assert_eq!(
+            parse_recovery_reply("\x02kirc\x02 has been ghosted."),
+            Some(RecoveryReply::Succeeded)
+        );
+        assert_eq!(
+            parse_recovery_reply("Ghost with your nick has been killed."),
+            Some(RecoveryReply::Succeeded)
+        );
+    }
+
+    #[test]
+    fn test_parse_recovery_reply_failure() {
+        assert_eq!(
+            parse_recovery_reply("Invalid password for \x02kirc\x02."),
+            Some(RecoveryReply::Failed(
+                "Invalid password for kirc.".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_recovery_reply("\x0304,01kirc\x03 is not online."),
+            Some(RecoveryReply::Failed("kirc is not online.".to_string()))
+        );
+        assert_eq!(parse_recovery_reply("You are now identified."), None);
+    }
+
+    #[test]
+    fn test_needs_nick_only_for_ghost_once() {
+        let ghost = NickRecovery::new(NickRecoveryMethod::Ghost);
+        assert!(ghost.needs_nick());
+        assert!(!ghost.with_nick_sent().needs_nick());
+        assert!(!NickRecovery::new(NickRecoveryMethod::Regain).needs_nick());
+    }
+}
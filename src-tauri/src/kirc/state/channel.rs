@@ -1,7 +1,392 @@
+use crate::kirc::casemap::{CaseMapping, IrcKey};
+use crate::kirc::isupport::PrefixMode;
+use crate::kirc::modes::{format_modes, ChannelModeKind};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub(in crate::kirc) struct ChannelState {
     pub(in crate::kirc) name: String,
     pub(in crate::kirc) locked: bool,
+    /// 강퇴당하면 몇 초 뒤 다시 들어갈지, None이면 채널 목록에서 제거
+    #[serde(default)]
+    pub(in crate::kirc) rejoin_after_kick: Option<u64>,
+    /// 현재 채널 멤버, 연결마다 NAMES로 다시 받으므로 저장하지 않음
+    #[serde(skip)]
+    members: HashMap<IrcKey, Member>,
+    /// RPL_NAMREPLY로 받는 중인 멤버, RPL_ENDOFNAMES에서 members와 교체
+    #[serde(skip)]
+    pending_names: Option<HashMap<IrcKey, Member>>,
+    /// 현재 채널 모드와 인자 (목록, 멤버 권한 모드 제외)
+    #[serde(skip)]
+    modes: BTreeMap<char, Option<String>>,
+    /// 들어갈 때 RPL_TOPIC으로 다시 받으므로 저장하지 않음
+    #[serde(skip)]
+    topic: Option<Topic>,
+    /// RPL_TOPIC으로 받았지만 RPL_TOPICWHOTIME을 받지 못해 아직 UI에 알리지 않음
+    #[serde(skip)]
+    topic_pending: bool,
+}
+
+/// 채널 토픽, set_at은 ms
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Topic {
+    text: String,
+    set_by: Option<String>,
+    set_at: Option<u64>,
+}
+
+/// 채널 멤버, modes는 PREFIX 순서(높은 권한 먼저)로 정렬된 모드 문자
+#[derive(Clone, Debug, PartialEq)]
+pub(in crate::kirc) struct Member {
+    nick: String,
+    modes: String,
+}
+
+/// 프론트 전달용 멤버, prefix는 높은 권한 먼저 (`@+`)
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChannelUser {
+    nick: String,
+    prefix: String,
+}
+
+impl ChannelState {
+    pub(in crate::kirc) fn new(name: &str, locked: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            locked,
+            rejoin_after_kick: None,
+            members: HashMap::new(),
+            pending_names: None,
+            modes: BTreeMap::new(),
+            topic: None,
+            topic_pending: false,
+        }
+    }
+
+    pub(in crate::kirc) fn topic(&self) -> Option<&Topic> {
+        self.topic.as_ref()
+    }
+
+    /// 빈 토픽은 토픽 없음으로 처리
+    pub(in crate::kirc) fn set_topic(
+        &mut self,
+        text: Option<String>,
+        set_by: Option<String>,
+        set_at: Option<u64>,
+    ) {
+        self.topic = text.filter(|text| !text.is_empty()).map(|text| Topic {
+            text,
+            set_by,
+            set_at,
+        });
+        self.topic_pending = false;
+    }
+
+    /// RPL_TOPIC, 설정자와 시각은 RPL_TOPICWHOTIME에서 받은 뒤 알림
+    pub(in crate::kirc) fn received_topic(&mut self, text: String) {
+        self.set_topic(Some(text), None, None);
+        self.topic_pending = self.topic.is_some();
+    }
+
+    /// RPL_TOPICWHOTIME 없이 RPL_ENDOFNAMES까지 온 경우 아직 알리지 않은 토픽
+    pub(in crate::kirc) fn take_pending_topic(&mut self) -> Option<&Topic> {
+        if !std::mem::take(&mut self.topic_pending) {
+            return None;
+        }
+        self.topic.as_ref()
+    }
+
+    /// RPL_TOPICWHOTIME, RPL_TOPIC 다음에 설정자와 시각을 받음
+    pub(in crate::kirc) fn set_topic_who_time(&mut self, set_by: String, set_at: Option<u64>) {
+        self.topic_pending = false;
+        if let Some(topic) = &mut self.topic {
+            topic.set_by = Some(set_by);
+            topic.set_at = set_at;
+        }
+    }
+
+    /// `+ntl 10` 형태, 모드가 없으면 빈 문자열
+    pub(in crate::kirc) fn mode_string(&self) -> String {
+        format_modes(&self.modes)
+    }
+
+    pub(in crate::kirc) fn set_mode(
+        &mut self,
+        kind: ChannelModeKind,
+        mode: char,
+        set: bool,
+        arg: Option<String>,
+    ) {
+        match kind {
+            ChannelModeKind::List | ChannelModeKind::Member => {}
+            _ if !set => {
+                self.modes.remove(&mode);
+            }
+            ChannelModeKind::AlwaysParam | ChannelModeKind::SetParam => {
+                self.modes.insert(mode, arg);
+            }
+            ChannelModeKind::Flag => {
+                self.modes.insert(mode, None);
+            }
+        }
+    }
+
+    /// RPL_CHANNELMODEIS로 전체 모드를 다시 받음
+    pub(in crate::kirc) fn clear_modes(&mut self) {
+        self.modes.clear();
+    }
+
+    /// 높은 권한, 닉네임 순으로 정렬
+    pub(in crate::kirc) fn users(&self, prefix: &[PrefixMode]) -> Vec<ChannelUser> {
+        let mut members: Vec<&Member> = self.members.values().collect();
+        members.sort_by_key(|member| (member.rank(prefix), member.nick.to_lowercase()));
+        members
+            .into_iter()
+            .map(|member| member.to_user(prefix))
+            .collect()
+    }
+
+    pub(in crate::kirc) fn add_member(&mut self, key: IrcKey, member: Member) {
+        self.members.insert(key, member);
+    }
+
+    pub(in crate::kirc) fn remove_member(&mut self, key: &IrcKey) -> Option<Member> {
+        self.members.remove(key)
+    }
+
+    pub(in crate::kirc) fn clear_members(&mut self) {
+        self.members.clear();
+        self.pending_names = None;
+    }
+
+    pub(in crate::kirc) fn rename_member(
+        &mut self,
+        old: &IrcKey,
+        new: IrcKey,
+        nick: &str,
+    ) -> Option<&Member> {
+        let mut member = self.members.remove(old)?;
+        member.nick = nick.to_string();
+        self.members.insert(new.clone(), member);
+        self.members.get(&new)
+    }
+
+    pub(in crate::kirc) fn set_member_mode(
+        &mut self,
+        key: &IrcKey,
+        mode: char,
+        set: bool,
+        prefix: &[PrefixMode],
+    ) -> Option<&Member> {
+        let member = self.members.get_mut(key)?;
+        if set {
+            if !member.modes.contains(mode) {
+                member.modes.push(mode);
+                member.sort_modes(prefix);
+            }
+        } else {
+            member.modes.retain(|m| m != mode);
+        }
+        Some(member)
+    }
+
+    pub(in crate::kirc) fn add_names(
+        &mut self,
+        members: impl IntoIterator<Item = (IrcKey, Member)>,
+    ) {
+        self.pending_names
+            .get_or_insert_with(HashMap::new)
+            .extend(members);
+    }
+
+    /// NAMES 응답이 끝나면 받은 목록으로 교체
+    pub(in crate::kirc) fn finish_names(&mut self) {
+        self.members = self.pending_names.take().unwrap_or_default();
+    }
+
+    /// CASEMAPPING이 바뀌면 멤버 키도 다시 접음
+    pub(in crate::kirc) fn refold(&mut self, mapping: CaseMapping) {
+        self.members = fold_members(mapping, std::mem::take(&mut self.members));
+        self.pending_names = self
+            .pending_names
+            .take()
+            .map(|pending| fold_members(mapping, pending));
+    }
+}
+
+fn fold_members(mapping: CaseMapping, members: HashMap<IrcKey, Member>) -> HashMap<IrcKey, Member> {
+    members
+        .into_values()
+        .map(|member| (IrcKey::new(mapping, &member.nick), member))
+        .collect()
+}
+
+impl Topic {
+    pub(in crate::kirc) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(in crate::kirc) fn set_by(&self) -> Option<&str> {
+        self.set_by.as_deref()
+    }
+
+    pub(in crate::kirc) fn set_at(&self) -> Option<u64> {
+        self.set_at
+    }
+}
+
+impl Member {
+    pub(in crate::kirc) fn new(nick: &str) -> Self {
+        Self {
+            nick: nick.to_string(),
+            modes: String::new(),
+        }
+    }
+
+    /// NAMES 항목 파싱 (`@+nick`, userhost-in-names면 `@nick!user@host`)
+    pub(in crate::kirc) fn from_names_entry(entry: &str, prefix: &[PrefixMode]) -> Self {
+        let nick = entry.trim_start_matches(|c| prefix.iter().any(|p| p.prefix == c));
+        let modes = entry[..entry.len() - nick.len()]
+            .chars()
+            .filter_map(|c| prefix.iter().find(|p| p.prefix == c).map(|p| p.mode))
+            .collect();
+        let nick = nick.split('!').next().unwrap_or(nick);
+
+        let mut member = Self {
+            nick: nick.to_string(),
+            modes,
+        };
+        member.sort_modes(prefix);
+        member
+    }
+
+    pub(in crate::kirc) fn nick(&self) -> &str {
+        &self.nick
+    }
+
+    pub(in crate::kirc) fn to_user(&self, prefix: &[PrefixMode]) -> ChannelUser {
+        ChannelUser {
+            nick: self.nick.clone(),
+            prefix: self
+                .modes
+                .chars()
+                .filter_map(|m| prefix.iter().find(|p| p.mode == m).map(|p| p.prefix))
+                .collect(),
+        }
+    }
+
+    /// 가장 높은 권한의 PREFIX 순서, 권한이 없으면 가장 낮음
+    fn rank(&self, prefix: &[PrefixMode]) -> usize {
+        self.modes
+            .chars()
+            .filter_map(|m| prefix.iter().position(|p| p.mode == m))
+            .min()
+            .unwrap_or(prefix.len())
+    }
+
+    fn sort_modes(&mut self, prefix: &[PrefixMode]) {
+        let mut modes: Vec<char> = self.modes.chars().collect();
+        modes.sort_by_key(|m| prefix.iter().position(|p| p.mode == *m));
+        self.modes = modes.into_iter().collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefix() -> Vec<PrefixMode> {
+        "qaohv"
+            .chars()
+            .zip("~&@%+".chars())
+            .map(|(mode, prefix)| PrefixMode { mode, prefix })
+            .collect()
+    }
+
+    fn key(nick: &str) -> IrcKey {
+        IrcKey::new(CaseMapping::Rfc1459, nick)
+    }
+
+    #[test]
+    fn test_names_entry() {
+        let prefix = prefix();
+
+        let member = Member::from_names_entry("+@alice", &prefix);
+        assert_eq!(member.nick(), "alice");
+        assert_eq!(member.to_user(&prefix).prefix, "@+");
+
+        let member = Member::from_names_entry("%bob!b@example.com", &prefix);
+        assert_eq!(member.nick(), "bob");
+        assert_eq!(member.to_user(&prefix).prefix, "%");
+
+        let member = Member::from_names_entry("carol", &prefix);
+        assert_eq!(member.to_user(&prefix).prefix, "");
+    }
+
+    #[test]
+    fn test_names_replace_members() {
+        let prefix = prefix();
+        let mut channel = ChannelState::new("#rust", false);
+        channel.add_member(key("old"), Member::new("old"));
+
+        channel.add_names(["@op", "voiced"].map(|entry| {
+            let member = Member::from_names_entry(entry, &prefix);
+            (key(member.nick()), member)
+        }));
+        assert_eq!(channel.users(&prefix)[0].nick, "old");
+
+        channel.finish_names();
+        let users = channel.users(&prefix);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].nick, "op");
+        assert!(users.iter().all(|user| user.nick != "old"));
+    }
+
+    #[test]
+    fn test_topic() {
+        let mut channel = ChannelState::new("#rust", false);
+        channel.set_topic(Some("Welcome".to_string()), None, None);
+        channel.set_topic_who_time("alice".to_string(), Some(1_000));
+
+        let topic = channel.topic().unwrap();
+        assert_eq!(topic.text, "Welcome");
+        assert_eq!(topic.set_by.as_deref(), Some("alice"));
+        assert_eq!(topic.set_at, Some(1_000));
+
+        channel.set_topic(Some(String::new()), Some("bob".to_string()), Some(2_000));
+        assert!(channel.topic().is_none());
+
+        // RPL_TOPICWHOTIME을 받으면 그때 알렸으므로 NAMES 끝에서 다시 알리지 않음
+        channel.received_topic("Welcome".to_string());
+        channel.set_topic_who_time("alice".to_string(), None);
+        assert!(channel.take_pending_topic().is_none());
+
+        channel.received_topic("Welcome".to_string());
+        assert_eq!(channel.take_pending_topic().unwrap().text, "Welcome");
+        assert!(channel.take_pending_topic().is_none());
+    }
+
+    #[test]
+    fn test_member_mode_and_rename() {
+        let prefix = prefix();
+        let mut channel = ChannelState::new("#rust", false);
+        channel.add_member(key("Nick[a]"), Member::new("Nick[a]"));
+
+        channel.set_member_mode(&key("nick{a}"), 'v', true, &prefix);
+        channel.set_member_mode(&key("nick{a}"), 'o', true, &prefix);
+        assert_eq!(channel.users(&prefix)[0].prefix, "@+");
+
+        channel.set_member_mode(&key("NICK[A]"), 'o', false, &prefix);
+        assert_eq!(channel.users(&prefix)[0].prefix, "+");
+
+        let renamed = channel.rename_member(&key("nick[a]"), key("other"), "Other");
+        assert_eq!(renamed.map(Member::nick), Some("Other"));
+        assert!(channel
+            .rename_member(&key("nick[a]"), key("x"), "x")
+            .is_none());
+        assert_eq!(channel.users(&prefix)[0].prefix, "+");
+    }
 }
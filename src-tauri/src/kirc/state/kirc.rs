@@ -39,6 +39,10 @@ impl KircState {
         Ok(server_id)
     }
 
+    pub(in crate::kirc) fn remove_server(&self, server_id: ServerId) -> Option<Arc<ServerState>> {
+        self.servers.lock().unwrap().remove(&server_id)
+    }
+
     pub(in crate::kirc) fn drain_runtimes(&self) -> Vec<ServerRuntime> {
         let mut guard = self.servers.lock().unwrap();
         guard
@@ -53,7 +57,7 @@ impl KircState {
             .unwrap_or(false)
     }
 
-    pub(in crate::kirc) fn save_snapshot(&self) -> anyhow::Result<()> {
+    pub(crate) fn save_snapshot(&self) -> anyhow::Result<()> {
         if let Some(path) = &self.persistence_path {
             let snapshot = self.snapshot();
             crate::fs::save(path, snapshot)
@@ -67,7 +71,8 @@ impl FromIterator<ServerStateSnapshot> for KircState {
     fn from_iter<T: IntoIterator<Item = ServerStateSnapshot>>(iter: T) -> Self {
         let mut server_map = HashMap::new();
         for server in iter {
-            server_map.insert(Uuid::now_v7(), Arc::new(server.restore()));
+            let server_id = server.id().unwrap_or_else(Uuid::now_v7);
+            server_map.insert(server_id, Arc::new(server.restore()));
         }
 
         Self {
@@ -80,6 +85,10 @@ impl FromIterator<ServerStateSnapshot> for KircState {
 impl Originator<KircStateSnapshot> for KircState {
     fn snapshot(&self) -> KircStateSnapshot {
         let servers = self.servers.lock().unwrap();
-        KircStateSnapshot::from_iter(servers.values().map(|state| state.snapshot()))
+        KircStateSnapshot::from_iter(
+            servers
+                .iter()
+                .map(|(id, state)| state.snapshot().with_id(*id)),
+        )
     }
 }
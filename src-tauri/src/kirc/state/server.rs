@@ -1,13 +1,22 @@
+use crate::kirc::cap::CapState;
+use crate::kirc::casemap::{CaseMapping, IrcKey};
+use crate::kirc::client::SendQueue;
+use crate::kirc::isupport::{ISupport, PrefixMode};
+use crate::kirc::modes::{ChannelModeKind, ModeChange};
 use crate::kirc::persistence::ServerStateSnapshot;
-use crate::kirc::state::channel::ChannelState;
-use crate::kirc::types::server::ServerConfig;
-use crate::kirc::types::{ChannelId, ServerCommand, ServerStatus};
+use crate::kirc::sasl::SaslProgress;
+use crate::kirc::services::NickRecovery;
+use crate::kirc::state::channel::{ChannelState, ChannelUser, Member, Topic};
+use crate::kirc::throttle::{CtcpLimiter, CtcpVerdict};
+use crate::kirc::types::server::{AutoConnect, Endpoint, ServerConfig};
+use crate::kirc::types::{ChannelId, EndpointFailure, ServerCommand, ServerStatus};
 use crate::memento::Originator;
 use anyhow::anyhow;
-use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::Duration;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use tokio::time::timeout;
 use tracing::debug;
@@ -30,9 +39,17 @@ pub(in crate::kirc) enum ServerRuntime {
     Disconnecting {
         handle: JoinHandle<()>,
     },
+    Reconnecting {
+        handle: JoinHandle<()>,
+        attempt: u32,
+        next_retry_at: u64,
+    },
     Failed {
         error: String,
     },
+    AuthFailed {
+        error: String,
+    },
 }
 
 impl ServerRuntime {
@@ -43,7 +60,16 @@ impl ServerRuntime {
             ServerRuntime::Registering { .. } => ServerStatus::Registering,
             ServerRuntime::Connected { .. } => ServerStatus::Connected,
             ServerRuntime::Disconnecting { .. } => ServerStatus::Disconnecting,
+            ServerRuntime::Reconnecting {
+                attempt,
+                next_retry_at,
+                ..
+            } => ServerStatus::Reconnecting {
+                attempt: *attempt,
+                next_retry_at: *next_retry_at,
+            },
             ServerRuntime::Failed { .. } => ServerStatus::Failed,
+            ServerRuntime::AuthFailed { .. } => ServerStatus::AuthFailed,
         }
     }
 
@@ -51,36 +77,67 @@ impl ServerRuntime {
         const TIMEOUT: Duration = Duration::from_secs(5);
 
         match self {
-            ServerRuntime::Connected { tx, handle } | ServerRuntime::Registering { tx, handle } => {
+            ServerRuntime::Connected { tx, mut handle }
+            | ServerRuntime::Registering { tx, mut handle } => {
                 // 1. QUIT 전송
                 debug!(command = %ServerCommand::Quit, "tx send");
                 let _ = tx.send(ServerCommand::Quit);
 
-                // 2. 정상 종료 대기 (timeout optional)
-                let _ = timeout(TIMEOUT, handle).await;
+                // 2. 정상 종료 대기, 시간 초과시 강제 종료
+                if timeout(TIMEOUT, &mut handle).await.is_err() {
+                    handle.abort();
+                }
             }
 
-            ServerRuntime::Connecting { handle } => {
+            ServerRuntime::Connecting { handle } | ServerRuntime::Reconnecting { handle, .. } => {
                 handle.abort();
                 let _ = handle.await;
             }
 
-            ServerRuntime::Disconnecting { handle } => {
-                let _ = timeout(TIMEOUT, handle).await;
+            ServerRuntime::Disconnecting { mut handle } => {
+                if timeout(TIMEOUT, &mut handle).await.is_err() {
+                    handle.abort();
+                }
             }
 
-            ServerRuntime::Disconnected | ServerRuntime::Failed { .. } => {
+            ServerRuntime::Disconnected
+            | ServerRuntime::Failed { .. }
+            | ServerRuntime::AuthFailed { .. } => {
                 // nothing
             }
         }
     }
 }
 
+/// TOFU 승인을 기다리는 주소, 서버 인증서 fingerprint와 응답 채널
+pub(in crate::kirc) type TlsApproval = (Endpoint, String, oneshot::Sender<bool>);
+
 pub(in crate::kirc) struct ServerState {
     runtime: Mutex<ServerRuntime>,
     config: Mutex<ServerConfig>,
-    channels: Mutex<HashMap<ChannelId, ChannelState>>,
+    /// CASEMAPPING으로 접은 채널 이름을 키로 사용, 표시용 이름은 ChannelState.name
+    channels: Mutex<HashMap<IrcKey, ChannelState>>,
+    /// 열린 개인 대화, 상대 닉네임을 접은 키와 표시용 닉네임
+    queries: Mutex<HashMap<IrcKey, String>>,
     current_nickname: Mutex<String>,
+    sasl_progress: Mutex<SaslProgress>,
+    caps: Mutex<CapState>,
+    /// 등록 중 시도한 fallback 닉네임 수
+    nick_fallback_index: Mutex<usize>,
+    nick_recovery: Mutex<Option<NickRecovery>>,
+    tls_approval: Mutex<Option<TlsApproval>>,
+    isupport: Mutex<ISupport>,
+    /// 자신의 유저 모드
+    user_modes: Mutex<BTreeSet<char>>,
+    ctcp_limiter: Mutex<CtcpLimiter>,
+    /// 속도 제한으로 대기 중인 메세지, 연결마다 writer가 비우고 사용
+    send_queue: Arc<SendQueue>,
+    /// 마지막으로 연결에 성공한 주소
+    connected_endpoint: Mutex<Option<Endpoint>>,
+    /// 마지막 연결 시도에서 실패한 주소와 이유
+    endpoint_failures: Mutex<Vec<EndpointFailure>>,
+    /// 지난 실행 종료 시점에 연결되어 있었는지 (AutoConnect::LastSession)
+    connected_last_session: bool,
 }
 
 impl ServerState {
@@ -90,18 +147,49 @@ impl ServerState {
             current_nickname: Mutex::new(config.nickname().to_string()),
             config: Mutex::new(config),
             channels: Mutex::new(HashMap::new()),
+            queries: Mutex::new(HashMap::new()),
+            sasl_progress: Mutex::new(SaslProgress::default()),
+            caps: Mutex::new(CapState::default()),
+            nick_fallback_index: Mutex::new(0),
+            nick_recovery: Mutex::new(None),
+            tls_approval: Mutex::new(None),
+            isupport: Mutex::new(ISupport::default()),
+            user_modes: Mutex::new(BTreeSet::new()),
+            ctcp_limiter: Mutex::new(CtcpLimiter::new(Instant::now())),
+            send_queue: Arc::new(SendQueue::new()),
+            connected_endpoint: Mutex::new(None),
+            endpoint_failures: Mutex::new(Vec::new()),
+            connected_last_session: false,
         }
     }
 
     pub(in crate::kirc) fn with_channel(
         config: ServerConfig,
         channels: HashMap<ChannelId, ChannelState>,
+        queries: Vec<String>,
+        connected_last_session: bool,
     ) -> Self {
         Self {
             runtime: Mutex::new(ServerRuntime::Disconnected),
             current_nickname: Mutex::new(config.nickname().to_string()),
             config: Mutex::new(config),
-            channels: Mutex::new(channels),
+            channels: Mutex::new(fold_channels(
+                CaseMapping::default(),
+                channels.into_values(),
+            )),
+            queries: Mutex::new(fold_queries(CaseMapping::default(), queries)),
+            sasl_progress: Mutex::new(SaslProgress::default()),
+            caps: Mutex::new(CapState::default()),
+            nick_fallback_index: Mutex::new(0),
+            nick_recovery: Mutex::new(None),
+            tls_approval: Mutex::new(None),
+            isupport: Mutex::new(ISupport::default()),
+            user_modes: Mutex::new(BTreeSet::new()),
+            ctcp_limiter: Mutex::new(CtcpLimiter::new(Instant::now())),
+            send_queue: Arc::new(SendQueue::new()),
+            connected_endpoint: Mutex::new(None),
+            endpoint_failures: Mutex::new(Vec::new()),
+            connected_last_session,
         }
     }
 
@@ -113,22 +201,344 @@ impl ServerState {
         self.config.lock().unwrap().clone()
     }
 
+    pub(in crate::kirc) fn set_config(&self, config: ServerConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    /// 표시용 이름을 키로 반환
     pub(in crate::kirc) fn channels(&self) -> HashMap<ChannelId, ChannelState> {
-        self.channels.lock().unwrap().clone()
+        self.channels
+            .lock()
+            .unwrap()
+            .values()
+            .map(|channel| (channel.name.clone(), channel.clone()))
+            .collect()
     }
 
+    /// 이미 있는 채널이면 처음 입력한 표시용 이름 유지
     pub(in crate::kirc) fn insert_channel(&self, channel_name: &str, locked: bool) {
-        self.channels.lock().unwrap().insert(
-            channel_name.to_string(),
-            ChannelState {
-                name: channel_name.to_string(),
-                locked,
-            },
-        );
+        let key = self.irc_key(channel_name);
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(key)
+            .and_modify(|channel| channel.locked = locked)
+            .or_insert_with(|| ChannelState::new(channel_name, locked));
     }
 
     pub(in crate::kirc) fn remove_channel(&self, channel_name: &str) -> Option<ChannelState> {
-        self.channels.lock().unwrap().remove(channel_name)
+        let key = self.irc_key(channel_name);
+        self.channels.lock().unwrap().remove(&key)
+    }
+
+    /// 대소문자가 달라도 알고 있는 채널이면 저장된 표시용 이름, 아니면 그대로
+    pub(in crate::kirc) fn channel_name(&self, channel_name: &str) -> String {
+        let key = self.irc_key(channel_name);
+        self.channels
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map(|channel| channel.name.clone())
+            .unwrap_or_else(|| channel_name.to_string())
+    }
+
+    /// 표시용 닉네임, 닉네임 순으로 정렬
+    pub(in crate::kirc) fn queries(&self) -> Vec<String> {
+        let mut queries: Vec<String> = self.queries.lock().unwrap().values().cloned().collect();
+        queries.sort_by_key(|nick| nick.to_lowercase());
+        queries
+    }
+
+    /// 이미 열린 대화면 저장된 표시용 닉네임, 새로 열었는지 함께 반환
+    pub(in crate::kirc) fn open_query(&self, nick: &str) -> (String, bool) {
+        let key = self.irc_key(nick);
+        let mut queries = self.queries.lock().unwrap();
+        match queries.get(&key) {
+            Some(name) => (name.clone(), false),
+            None => {
+                queries.insert(key, nick.to_string());
+                (nick.to_string(), true)
+            }
+        }
+    }
+
+    /// 닫은 대화의 표시용 닉네임 반환
+    pub(in crate::kirc) fn close_query(&self, nick: &str) -> Option<String> {
+        let key = self.irc_key(nick);
+        self.queries.lock().unwrap().remove(&key)
+    }
+
+    /// NICK, 열린 대화가 있으면 이전 표시용 닉네임 반환
+    pub(in crate::kirc) fn rename_query(&self, old_nick: &str, new_nick: &str) -> Option<String> {
+        let mapping = self.case_mapping();
+        let mut queries = self.queries.lock().unwrap();
+        let old = queries.remove(&IrcKey::new(mapping, old_nick))?;
+        queries.insert(IrcKey::new(mapping, new_nick), new_nick.to_string());
+        Some(old)
+    }
+
+    pub(in crate::kirc) fn channel_users(&self, channel: &str) -> Option<Vec<ChannelUser>> {
+        let key = self.irc_key(channel);
+        let prefix = self.isupport.lock().unwrap().prefix();
+        self.channels
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map(|channel| channel.users(&prefix))
+    }
+
+    /// RPL_NAMREPLY 한 줄의 멤버를 임시 목록에 추가
+    pub(in crate::kirc) fn add_names(&self, channel: &str, names: &str) {
+        let (mapping, prefix) = self.member_rules();
+        let members = names.split_whitespace().map(|entry| {
+            let member = Member::from_names_entry(entry, &prefix);
+            (IrcKey::new(mapping, member.nick()), member)
+        });
+
+        if let Some(channel) = self
+            .channels
+            .lock()
+            .unwrap()
+            .get_mut(&IrcKey::new(mapping, channel))
+        {
+            channel.add_names(members);
+        }
+    }
+
+    /// RPL_ENDOFNAMES, 완성된 멤버 목록 반환
+    pub(in crate::kirc) fn finish_names(&self, channel: &str) -> Option<Vec<ChannelUser>> {
+        let (mapping, prefix) = self.member_rules();
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.get_mut(&IrcKey::new(mapping, channel))?;
+        channel.finish_names();
+        Some(channel.users(&prefix))
+    }
+
+    /// 자신이 들어간 경우엔 NAMES를 다시 받으므로 기존 목록 비움
+    ///
+    /// perform, raw 명령이나 서버가 강제로 넣은 채널도 자신이 들어가면 추적 시작
+    pub(in crate::kirc) fn member_joined(&self, channel: &str, nick: &str) -> Option<ChannelUser> {
+        let (mapping, prefix) = self.member_rules();
+        let is_me = self.is_current_nickname(nick);
+        let mut channels = self.channels.lock().unwrap();
+        let key = IrcKey::new(mapping, channel);
+        let channel = if is_me {
+            channels
+                .entry(key)
+                .or_insert_with(|| ChannelState::new(channel, false))
+        } else {
+            channels.get_mut(&key)?
+        };
+        if is_me {
+            channel.clear_members();
+            channel.clear_modes();
+            channel.set_topic(None, None, None);
+        }
+
+        let member = Member::new(nick);
+        let user = member.to_user(&prefix);
+        channel.add_member(IrcKey::new(mapping, nick), member);
+        Some(user)
+    }
+
+    /// PART, KICK, 자신이 나간 경우엔 목록 비움
+    pub(in crate::kirc) fn member_left(&self, channel: &str, nick: &str) -> bool {
+        let mapping = self.case_mapping();
+        let is_me = self.is_current_nickname(nick);
+        let mut channels = self.channels.lock().unwrap();
+        let Some(channel) = channels.get_mut(&IrcKey::new(mapping, channel)) else {
+            return false;
+        };
+
+        if is_me {
+            channel.clear_members();
+            return true;
+        }
+        channel.remove_member(&IrcKey::new(mapping, nick)).is_some()
+    }
+
+    /// QUIT, 멤버가 있던 채널의 표시용 이름 반환
+    pub(in crate::kirc) fn member_quit(&self, nick: &str) -> Vec<String> {
+        let key = self.irc_key(nick);
+        self.channels
+            .lock()
+            .unwrap()
+            .values_mut()
+            .filter_map(|channel| channel.remove_member(&key).map(|_| channel.name.clone()))
+            .collect()
+    }
+
+    /// NICK, 멤버가 있던 채널의 표시용 이름과 바뀐 멤버 반환
+    pub(in crate::kirc) fn member_renamed(
+        &self,
+        old_nick: &str,
+        new_nick: &str,
+    ) -> Vec<(String, ChannelUser)> {
+        let (mapping, prefix) = self.member_rules();
+        let old = IrcKey::new(mapping, old_nick);
+        let new = IrcKey::new(mapping, new_nick);
+        self.channels
+            .lock()
+            .unwrap()
+            .values_mut()
+            .filter_map(|channel| {
+                let user = channel
+                    .rename_member(&old, new.clone(), new_nick)?
+                    .to_user(&prefix);
+                Some((channel.name.clone(), user))
+            })
+            .collect()
+    }
+
+    pub(in crate::kirc) fn set_channel_topic(
+        &self,
+        channel: &str,
+        text: Option<String>,
+        set_by: Option<String>,
+        set_at: Option<u64>,
+    ) {
+        let key = self.irc_key(channel);
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(&key) {
+            channel.set_topic(text, set_by, set_at);
+        }
+    }
+
+    pub(in crate::kirc) fn received_channel_topic(&self, channel: &str, text: String) {
+        let key = self.irc_key(channel);
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(&key) {
+            channel.received_topic(text);
+        }
+    }
+
+    /// RPL_TOPICWHOTIME을 받지 못해 아직 알리지 않은 토픽
+    pub(in crate::kirc) fn take_pending_topic(&self, channel: &str) -> Option<Topic> {
+        let key = self.irc_key(channel);
+        let mut channels = self.channels.lock().unwrap();
+        channels.get_mut(&key)?.take_pending_topic().cloned()
+    }
+
+    /// 설정자와 시각이 반영된 토픽 반환
+    pub(in crate::kirc) fn set_channel_topic_who_time(
+        &self,
+        channel: &str,
+        set_by: String,
+        set_at: Option<u64>,
+    ) -> Option<Topic> {
+        let key = self.irc_key(channel);
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.get_mut(&key)?;
+        channel.set_topic_who_time(set_by, set_at);
+        channel.topic().cloned()
+    }
+
+    /// 채널 MODE 적용, 권한이 바뀐 멤버 반환
+    pub(in crate::kirc) fn apply_channel_modes(
+        &self,
+        channel: &str,
+        changes: &[ModeChange],
+    ) -> Vec<ChannelUser> {
+        let (mapping, prefix) = self.member_rules();
+        let chanmodes = self.isupport.lock().unwrap().chanmodes();
+        let mut channels = self.channels.lock().unwrap();
+        let Some(channel) = channels.get_mut(&IrcKey::new(mapping, channel)) else {
+            return Vec::new();
+        };
+
+        let mut updated = Vec::new();
+        for change in changes {
+            let kind = ChannelModeKind::of(change.mode, &chanmodes, &prefix);
+            if kind != ChannelModeKind::Member {
+                channel.set_mode(kind, change.mode, change.set, change.arg.clone());
+                continue;
+            }
+
+            let Some(nick) = &change.arg else {
+                continue;
+            };
+            if let Some(member) = channel.set_member_mode(
+                &IrcKey::new(mapping, nick),
+                change.mode,
+                change.set,
+                &prefix,
+            ) {
+                updated.push(member.to_user(&prefix));
+            }
+        }
+
+        updated
+    }
+
+    /// RPL_CHANNELMODEIS, 현재 채널 모드 전체를 교체
+    pub(in crate::kirc) fn set_channel_modes(&self, channel: &str, changes: &[ModeChange]) {
+        let key = self.irc_key(channel);
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(&key) {
+            channel.clear_modes();
+        }
+        self.apply_channel_modes(channel, changes);
+    }
+
+    /// CTCP 요청에 응답해도 되는지, 채널로 온 요청은 더 엄격하게 제한
+    pub(in crate::kirc) fn check_ctcp(&self, source: &str, to_channel: bool) -> CtcpVerdict {
+        let key = self.irc_key(source);
+        self.ctcp_limiter
+            .lock()
+            .unwrap()
+            .check(key, to_channel, Instant::now())
+    }
+
+    pub(in crate::kirc) fn send_queue(&self) -> Arc<SendQueue> {
+        self.send_queue.clone()
+    }
+
+    /// `+iw` 형태, 모드가 없으면 빈 문자열
+    pub(in crate::kirc) fn user_modes(&self) -> String {
+        let modes = self.user_modes.lock().unwrap();
+        if modes.is_empty() {
+            return String::new();
+        }
+        std::iter::once('+').chain(modes.iter().copied()).collect()
+    }
+
+    /// 자신의 유저 MODE 적용, replace면 RPL_UMODEIS로 전체를 다시 받은 것
+    pub(in crate::kirc) fn apply_user_modes(&self, changes: &[ModeChange], replace: bool) {
+        let mut modes = self.user_modes.lock().unwrap();
+        if replace {
+            modes.clear();
+        }
+        for change in changes {
+            if change.set {
+                modes.insert(change.mode);
+            } else {
+                modes.remove(&change.mode);
+            }
+        }
+    }
+
+    fn member_rules(&self) -> (CaseMapping, Vec<PrefixMode>) {
+        let isupport = self.isupport.lock().unwrap();
+        (isupport.case_mapping(), isupport.prefix())
+    }
+
+    pub(in crate::kirc) fn case_mapping(&self) -> CaseMapping {
+        self.isupport.lock().unwrap().case_mapping()
+    }
+
+    pub(in crate::kirc) fn irc_key(&self, name: &str) -> IrcKey {
+        IrcKey::new(self.case_mapping(), name)
+    }
+
+    /// CASEMAPPING 기준 닉네임 비교
+    pub(in crate::kirc) fn nick_eq(&self, a: &str, b: &str) -> bool {
+        self.case_mapping().eq(a, b)
+    }
+
+    pub(in crate::kirc) fn is_current_nickname(&self, nickname: &str) -> bool {
+        self.nick_eq(&self.current_nickname(), nickname)
+    }
+
+    pub(in crate::kirc) fn is_primary_nickname(&self, nickname: &str) -> bool {
+        let primary = self.config.lock().unwrap().nickname().to_string();
+        self.nick_eq(&primary, nickname)
     }
 
     pub(in crate::kirc) fn current_nickname(&self) -> String {
@@ -139,30 +549,182 @@ impl ServerState {
         *self.current_nickname.lock().unwrap() = new_nick.to_string();
     }
 
+    /// 등록 중 닉네임이 거부되면 다음 fallback 닉네임 반환, 모두 소진되면 None
+    pub(in crate::kirc) fn next_fallback_nickname(&self) -> Option<String> {
+        let fallbacks = self.config.lock().unwrap().fallback_nicknames();
+        let mut index = self.nick_fallback_index.lock().unwrap();
+        let nick = fallbacks.get(*index).cloned();
+        if nick.is_some() {
+            *index += 1;
+        }
+        nick
+    }
+
+    pub(in crate::kirc) fn nick_recovery(&self) -> Option<NickRecovery> {
+        *self.nick_recovery.lock().unwrap()
+    }
+
+    pub(in crate::kirc) fn set_nick_recovery(&self, recovery: Option<NickRecovery>) {
+        *self.nick_recovery.lock().unwrap() = recovery;
+    }
+
+    pub(in crate::kirc) fn pin_tls_fingerprint(&self, endpoint: &Endpoint, fingerprint: &str) {
+        self.config
+            .lock()
+            .unwrap()
+            .pin_tls_fingerprint(endpoint, fingerprint.to_string());
+    }
+
+    pub(in crate::kirc) fn set_tls_approval(
+        &self,
+        endpoint: Endpoint,
+        fingerprint: String,
+        tx: oneshot::Sender<bool>,
+    ) {
+        *self.tls_approval.lock().unwrap() = Some((endpoint, fingerprint, tx));
+    }
+
+    pub(in crate::kirc) fn take_tls_approval(&self) -> Option<TlsApproval> {
+        self.tls_approval.lock().unwrap().take()
+    }
+
+    pub(in crate::kirc) fn sasl_progress(&self) -> SaslProgress {
+        *self.sasl_progress.lock().unwrap()
+    }
+
+    pub(in crate::kirc) fn set_sasl_progress(&self, progress: SaslProgress) {
+        *self.sasl_progress.lock().unwrap() = progress;
+    }
+
+    pub(in crate::kirc) fn update_caps<R>(&self, f: impl FnOnce(&mut CapState) -> R) -> R {
+        f(&mut self.caps.lock().unwrap())
+    }
+
+    pub(in crate::kirc) fn is_cap_enabled(&self, name: &str) -> bool {
+        self.caps.lock().unwrap().is_enabled(name)
+    }
+
+    pub(in crate::kirc) fn enabled_caps(&self) -> Vec<String> {
+        self.caps.lock().unwrap().enabled()
+    }
+
     pub(in crate::kirc) fn is_active(&self) -> bool {
         matches!(
             &*self.runtime.lock().unwrap(),
             ServerRuntime::Connecting { .. }
                 | ServerRuntime::Registering { .. }
                 | ServerRuntime::Connected { .. }
+                | ServerRuntime::Reconnecting { .. }
         )
     }
 
+    /// CASEMAPPING이 바뀌면 채널 키도 새 규칙으로 다시 접음
+    pub(in crate::kirc) fn update_isupport<R>(&self, f: impl FnOnce(&mut ISupport) -> R) -> R {
+        let (result, before, after) = {
+            let mut isupport = self.isupport.lock().unwrap();
+            let before = isupport.case_mapping();
+            let result = f(&mut isupport);
+            (result, before, isupport.case_mapping())
+        };
+
+        if before != after {
+            self.refold_channels(after);
+        }
+
+        result
+    }
+
+    fn refold_channels(&self, mapping: CaseMapping) {
+        let mut channels = self.channels.lock().unwrap();
+        let old = std::mem::take(&mut *channels);
+        *channels = fold_channels(mapping, old.into_values());
+
+        let mut queries = self.queries.lock().unwrap();
+        let old = std::mem::take(&mut *queries);
+        *queries = fold_queries(mapping, old.into_values());
+    }
+
+    pub(in crate::kirc) fn isupport(&self) -> ISupport {
+        self.isupport.lock().unwrap().clone()
+    }
+
+    /// ISUPPORT NETWORK가 있으면 네트워크 이름, 없으면 서버 주소
+    pub(in crate::kirc) fn display_name(&self) -> String {
+        match self.isupport.lock().unwrap().network() {
+            Some(network) => network.to_string(),
+            None => self.config.lock().unwrap().server().to_string(),
+        }
+    }
+
+    pub(in crate::kirc) fn connected_endpoint(&self) -> Option<Endpoint> {
+        self.connected_endpoint.lock().unwrap().clone()
+    }
+
+    pub(in crate::kirc) fn endpoint_failures(&self) -> Vec<EndpointFailure> {
+        self.endpoint_failures.lock().unwrap().clone()
+    }
+
+    /// 주소 목록을 시도한 결과 기록, 실패시 이전에 성공한 주소는 유지
+    pub(in crate::kirc) fn set_endpoint_result(
+        &self,
+        connected: Option<Endpoint>,
+        failures: Vec<EndpointFailure>,
+    ) {
+        if connected.is_some() {
+            *self.connected_endpoint.lock().unwrap() = connected;
+        }
+        *self.endpoint_failures.lock().unwrap() = failures;
+    }
+
+    /// 앱 시작시 자동으로 연결할지
+    pub(in crate::kirc) fn should_auto_connect(&self) -> bool {
+        match self.config.lock().unwrap().auto_connect() {
+            AutoConnect::Always => true,
+            AutoConnect::LastSession => self.connected_last_session,
+            AutoConnect::Never => false,
+        }
+    }
+
     pub(in crate::kirc) fn is_channel_locked(&self, channel: &str) -> bool {
+        let key = self.irc_key(channel);
         self.channels
             .lock()
             .unwrap()
-            .get(channel)
+            .get(&key)
             .map(|s| s.locked)
             .unwrap_or(false)
     }
 
     pub(in crate::kirc) fn set_channel_locked(&self, channel: &str, locked: bool) {
-        if let Some(channel) = self.channels.lock().unwrap().get_mut(channel) {
+        let key = self.irc_key(channel);
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(&key) {
             channel.locked = locked;
         }
     }
 
+    pub(in crate::kirc) fn set_channel_rejoin_after_kick(&self, channel: &str, delay: Option<u64>) {
+        let key = self.irc_key(channel);
+        if let Some(channel) = self.channels.lock().unwrap().get_mut(&key) {
+            channel.rejoin_after_kick = delay;
+        }
+    }
+
+    /// 강퇴당함, 자동 재입장이 설정되어 있으면 대기 시간 반환, 아니면 채널 제거
+    pub(in crate::kirc) fn kicked_from_channel(&self, channel: &str) -> Option<Duration> {
+        let key = self.irc_key(channel);
+        let mut channels = self.channels.lock().unwrap();
+        match channels
+            .get(&key)
+            .and_then(|channel| channel.rejoin_after_kick)
+        {
+            Some(delay) => Some(Duration::from_secs(delay)),
+            None => {
+                channels.remove(&key);
+                None
+            }
+        }
+    }
+
     pub(in crate::kirc) fn send_command(&self, cmd: ServerCommand) -> anyhow::Result<()> {
         let guard = self.runtime.lock().unwrap();
         match &*guard {
@@ -174,10 +736,21 @@ impl ServerState {
         }
     }
 
+    /// 현재 연결의 actor로 명령을 보내는 sender, 연결이 끝나면 전송 실패
+    pub(in crate::kirc) fn command_sender(&self) -> Option<UnboundedSender<ServerCommand>> {
+        match &*self.runtime.lock().unwrap() {
+            ServerRuntime::Connected { tx, .. } | ServerRuntime::Registering { tx, .. } => {
+                Some(tx.clone())
+            }
+            _ => None,
+        }
+    }
+
     pub(in crate::kirc) fn transition_to_connecting(&self, handle: JoinHandle<()>) {
         let mut guard = self.runtime.lock().unwrap();
-        if let ServerRuntime::Disconnected | ServerRuntime::Failed { .. } =
-            std::mem::take(&mut *guard)
+        if let ServerRuntime::Disconnected
+        | ServerRuntime::Failed { .. }
+        | ServerRuntime::AuthFailed { .. } = std::mem::take(&mut *guard)
         {
             *guard = ServerRuntime::Connecting { handle };
         }
@@ -187,6 +760,19 @@ impl ServerState {
         let mut guard = self.runtime.lock().unwrap();
         if let ServerRuntime::Connecting { handle } = std::mem::take(&mut *guard) {
             *guard = ServerRuntime::Registering { tx, handle };
+            *self.current_nickname.lock().unwrap() =
+                self.config.lock().unwrap().nickname().to_string();
+            *self.nick_fallback_index.lock().unwrap() = 0;
+            *self.nick_recovery.lock().unwrap() = None;
+            self.update_isupport(ISupport::reset);
+            // 새 연결에서 NAMES, MODE로 다시 받음
+            for channel in self.channels.lock().unwrap().values_mut() {
+                channel.clear_members();
+                channel.clear_modes();
+            }
+            self.user_modes.lock().unwrap().clear();
+            *self.sasl_progress.lock().unwrap() = SaslProgress::default();
+            self.caps.lock().unwrap().start_negotiation();
         }
     }
 
@@ -197,12 +783,66 @@ impl ServerState {
         }
     }
 
-    pub(in crate::kirc) fn transition_to_disconnected(&self) {
-        *self.runtime.lock().unwrap() = ServerRuntime::Disconnected;
+    /// 인증 실패로 이미 종료된 상태라면 유지하고 false 반환
+    pub(in crate::kirc) fn transition_to_disconnected(&self) -> bool {
+        let mut guard = self.runtime.lock().unwrap();
+        if let ServerRuntime::AuthFailed { .. } = &*guard {
+            return false;
+        }
+
+        *guard = ServerRuntime::Disconnected;
+        *self.caps.lock().unwrap() = CapState::default();
+        true
+    }
+
+    /// 재연결 대기로 전환. 사용자가 이미 종료/취소했다면 false 반환
+    pub(in crate::kirc) fn transition_to_reconnecting(
+        &self,
+        attempt: u32,
+        next_retry_at: u64,
+    ) -> bool {
+        let mut guard = self.runtime.lock().unwrap();
+        match std::mem::take(&mut *guard) {
+            ServerRuntime::Connecting { handle }
+            | ServerRuntime::Registering { handle, .. }
+            | ServerRuntime::Connected { handle, .. } => {
+                *guard = ServerRuntime::Reconnecting {
+                    handle,
+                    attempt,
+                    next_retry_at,
+                };
+                *self.caps.lock().unwrap() = CapState::default();
+                true
+            }
+            other => {
+                *guard = other;
+                false
+            }
+        }
+    }
+
+    /// 재연결 대기가 끝나 다시 연결 시도
+    pub(in crate::kirc) fn resume_connecting(&self) -> bool {
+        let mut guard = self.runtime.lock().unwrap();
+        match std::mem::take(&mut *guard) {
+            ServerRuntime::Reconnecting { handle, .. } => {
+                *guard = ServerRuntime::Connecting { handle };
+                true
+            }
+            other => {
+                *guard = other;
+                false
+            }
+        }
     }
 
     pub(in crate::kirc) fn transition_to_failed(&self, error: String) {
         *self.runtime.lock().unwrap() = ServerRuntime::Failed { error };
+        *self.caps.lock().unwrap() = CapState::default();
+    }
+
+    pub(in crate::kirc) fn transition_to_auth_failed(&self, error: String) {
+        *self.runtime.lock().unwrap() = ServerRuntime::AuthFailed { error };
     }
 
     pub(in crate::kirc) fn disconnect(&self) {
@@ -213,30 +853,120 @@ impl ServerState {
                 let _ = tx.send(ServerCommand::Quit);
                 *guard = ServerRuntime::Disconnecting { handle };
             }
+            ServerRuntime::Reconnecting { handle, .. } => {
+                handle.abort();
+                *guard = ServerRuntime::Disconnected;
+            }
             other => {
                 *guard = other;
             }
         }
     }
 
+    /// 연결 시도 또는 재연결 대기를 취소
     pub(in crate::kirc) fn abort_connecting(&self) -> bool {
         let mut guard = self.runtime.lock().unwrap();
-        if let ServerRuntime::Connecting { handle } = std::mem::take(&mut *guard) {
-            handle.abort();
-            *guard = ServerRuntime::Disconnected;
-            true
-        } else {
-            false
+        match std::mem::take(&mut *guard) {
+            ServerRuntime::Connecting { handle } | ServerRuntime::Reconnecting { handle, .. } => {
+                handle.abort();
+                *guard = ServerRuntime::Disconnected;
+                // 중단된 연결 시도의 인증서는 더 이상 승인할 수 없음
+                self.take_tls_approval();
+                true
+            }
+            other => {
+                *guard = other;
+                false
+            }
         }
     }
 
     pub(in crate::kirc) fn take_runtime(&self) -> ServerRuntime {
+        self.take_tls_approval();
         std::mem::take(&mut *self.runtime.lock().unwrap())
     }
 }
 
+/// 표시용 이름을 주어진 CASEMAPPING으로 접어 채널 맵 구성
+fn fold_channels(
+    mapping: CaseMapping,
+    channels: impl IntoIterator<Item = ChannelState>,
+) -> HashMap<IrcKey, ChannelState> {
+    channels
+        .into_iter()
+        .map(|mut channel| {
+            channel.refold(mapping);
+            (IrcKey::new(mapping, &channel.name), channel)
+        })
+        .collect()
+}
+
+fn fold_queries(
+    mapping: CaseMapping,
+    queries: impl IntoIterator<Item = String>,
+) -> HashMap<IrcKey, String> {
+    queries
+        .into_iter()
+        .map(|nick| (IrcKey::new(mapping, &nick), nick))
+        .collect()
+}
+
 impl Originator<ServerStateSnapshot> for ServerState {
     fn snapshot(&self) -> ServerStateSnapshot {
-        ServerStateSnapshot::new(self.config(), self.channels())
+        ServerStateSnapshot::new(
+            self.config(),
+            self.channels(),
+            self.queries(),
+            self.is_active(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> ServerState {
+        let config = ServerConfig::new(
+            "irc.example.com".to_string(),
+            6697,
+            true,
+            "kirc".to_string(),
+        );
+        ServerState::new(ServerRuntime::Disconnected, config)
+    }
+
+    #[test]
+    fn test_own_join_tracks_unknown_channel() {
+        let server = server();
+
+        // 다른 사람의 JOIN으로는 채널을 만들지 않음
+        assert!(server.member_joined("#perform", "alice").is_none());
+        assert!(server.member_joined("#perform", "KIRC").is_some());
+        assert!(server.member_joined("#perform", "alice").is_some());
+
+        server.add_names("#PERFORM", "@kirc bob");
+        let users = server.finish_names("#perform").unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(serde_json::to_value(&users[0]).unwrap()["prefix"], "@");
+        assert!(server.channels().contains_key("#perform"));
+    }
+
+    #[test]
+    fn test_kicked_channel_is_removed_unless_rejoin_enabled() {
+        let server = server();
+        server.insert_channel("#Rust", false);
+        server.insert_channel("#kirc", false);
+        server.set_channel_rejoin_after_kick("#KIRC", Some(5));
+
+        assert!(server.kicked_from_channel("#rust").is_none());
+        assert_eq!(
+            server.kicked_from_channel("#KIRC"),
+            Some(Duration::from_secs(5))
+        );
+
+        let channels = server.channels();
+        assert!(!channels.contains_key("#Rust"));
+        assert!(channels.contains_key("#kirc"));
     }
 }
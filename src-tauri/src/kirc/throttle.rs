@@ -0,0 +1,271 @@
+use crate::kirc::casemap::IrcKey;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 보낸 사람 한 명이 연속으로 받을 수 있는 CTCP 응답 수와 충전 간격
+const SOURCE_BURST: u32 = 3;
+const SOURCE_INTERVAL: Duration = Duration::from_secs(10);
+/// 채널로 온 CTCP는 여러 사람이 동시에 보내는 경우가 많아 따로 더 적게 허용
+const CHANNEL_BURST: u32 = 2;
+const CHANNEL_INTERVAL: Duration = Duration::from_secs(10);
+const GLOBAL_BURST: u32 = 5;
+const GLOBAL_INTERVAL: Duration = Duration::from_secs(3);
+/// 보낸 사람별 버킷이 이보다 많아지면 가득 찬 버킷부터 정리
+const MAX_TRACKED_SOURCES: usize = 256;
+
+/// 최대 capacity개까지 모아두고 interval마다 하나씩 채우는 토큰 버킷
+#[derive(Clone, Debug)]
+pub(in crate::kirc) struct TokenBucket {
+    capacity: u32,
+    interval: Duration,
+    tokens: u32,
+    last_refill: Instant,
+    /// 토큰이 없어 막힌 상태인지, 연속으로 막힐 때 한 번만 알리기 위함
+    exhausted: bool,
+}
+
+impl TokenBucket {
+    pub(in crate::kirc) fn new(capacity: u32, interval: Duration, now: Instant) -> Self {
+        Self {
+            capacity,
+            interval,
+            tokens: capacity,
+            last_refill: now,
+            exhausted: false,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled = (elapsed.as_nanos() / self.interval.as_nanos().max(1))
+            .min(u128::from(self.capacity)) as u32;
+        if refilled == 0 {
+            return;
+        }
+
+        self.tokens = (self.tokens + refilled).min(self.capacity);
+        self.last_refill = if self.tokens == self.capacity {
+            now
+        } else {
+            self.last_refill + self.interval * refilled
+        };
+    }
+
+    /// 토큰이 있으면 하나 사용
+    pub(in crate::kirc) fn try_take(&mut self, now: Instant) -> bool {
+        if !self.has_token(now) {
+            return false;
+        }
+        self.take();
+        true
+    }
+
+    /// 다음 토큰까지 남은 시간, 토큰이 있으면 0
+    pub(in crate::kirc) fn until_next(&mut self, now: Instant) -> Duration {
+        if self.has_token(now) {
+            return Duration::ZERO;
+        }
+        (self.last_refill + self.interval).saturating_duration_since(now)
+    }
+
+    fn has_token(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens > 0
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1;
+        self.exhausted = false;
+    }
+
+    /// 막혔음을 기록, 이번에 처음 막혔으면 true
+    fn deny(&mut self) -> bool {
+        !std::mem::replace(&mut self.exhausted, true)
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens == self.capacity
+    }
+}
+
+/// 어떤 제한에 걸렸는지
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(in crate::kirc) enum CtcpFlood {
+    Source,
+    Channel,
+    Global,
+}
+
+#[derive(Debug, PartialEq)]
+pub(in crate::kirc) enum CtcpVerdict {
+    Reply,
+    /// 응답하지 않음, flood가 시작된 경우에만 notify
+    Ignore {
+        notify: Option<CtcpFlood>,
+    },
+}
+
+/// CTCP 응답 제한, 응답 NOTICE로 excess flood 당하지 않도록 함
+pub(in crate::kirc) struct CtcpLimiter {
+    global: TokenBucket,
+    channel: TokenBucket,
+    sources: HashMap<IrcKey, TokenBucket>,
+}
+
+impl CtcpLimiter {
+    pub(in crate::kirc) fn new(now: Instant) -> Self {
+        Self {
+            global: TokenBucket::new(GLOBAL_BURST, GLOBAL_INTERVAL, now),
+            channel: TokenBucket::new(CHANNEL_BURST, CHANNEL_INTERVAL, now),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// 보낸 사람, 채널, 전체 버킷에 모두 토큰이 있을 때만 응답
+    pub(in crate::kirc) fn check(
+        &mut self,
+        source: IrcKey,
+        to_channel: bool,
+        now: Instant,
+    ) -> CtcpVerdict {
+        if self.sources.len() >= MAX_TRACKED_SOURCES && !self.sources.contains_key(&source) {
+            self.sources.retain(|_, bucket| !bucket.is_full(now));
+        }
+
+        let source = self
+            .sources
+            .entry(source)
+            .or_insert_with(|| TokenBucket::new(SOURCE_BURST, SOURCE_INTERVAL, now));
+
+        let blocked = if !source.has_token(now) {
+            Some((&mut *source, CtcpFlood::Source))
+        } else if to_channel && !self.channel.has_token(now) {
+            Some((&mut self.channel, CtcpFlood::Channel))
+        } else if !self.global.has_token(now) {
+            Some((&mut self.global, CtcpFlood::Global))
+        } else {
+            None
+        };
+
+        if let Some((bucket, flood)) = blocked {
+            return CtcpVerdict::Ignore {
+                notify: bucket.deny().then_some(flood),
+            };
+        }
+
+        source.take();
+        if to_channel {
+            self.channel.take();
+        }
+        self.global.take();
+        CtcpVerdict::Reply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kirc::casemap::CaseMapping;
+
+    fn key(nick: &str) -> IrcKey {
+        IrcKey::new(CaseMapping::Rfc1459, nick)
+    }
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2, Duration::from_secs(1), now);
+        bucket.take();
+        bucket.take();
+        assert!(!bucket.has_token(now));
+
+        assert!(bucket.has_token(now + Duration::from_millis(1500)));
+        bucket.take();
+        // 남은 0.5초는 다음 충전에 이어서 계산
+        assert!(bucket.has_token(now + Duration::from_secs(2)));
+        assert!(bucket.is_full(now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_token_bucket_until_next() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1, Duration::from_secs(2), now);
+        assert_eq!(bucket.until_next(now), Duration::ZERO);
+        assert!(bucket.try_take(now));
+        assert!(!bucket.try_take(now));
+
+        let later = now + Duration::from_millis(500);
+        assert_eq!(bucket.until_next(later), Duration::from_millis(1500));
+        assert!(bucket.try_take(now + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_source_flood_notifies_once() {
+        let now = Instant::now();
+        let mut limiter = CtcpLimiter::new(now);
+
+        for _ in 0..SOURCE_BURST {
+            assert_eq!(
+                limiter.check(key("spammer"), false, now),
+                CtcpVerdict::Reply
+            );
+        }
+        assert_eq!(
+            limiter.check(key("Spammer"), false, now),
+            CtcpVerdict::Ignore {
+                notify: Some(CtcpFlood::Source)
+            }
+        );
+        assert_eq!(
+            limiter.check(key("spammer"), false, now),
+            CtcpVerdict::Ignore { notify: None }
+        );
+
+        // 다른 사람은 영향 없음
+        assert_eq!(limiter.check(key("friend"), false, now), CtcpVerdict::Reply);
+
+        let later = now + SOURCE_INTERVAL;
+        assert_eq!(
+            limiter.check(key("spammer"), false, later),
+            CtcpVerdict::Reply
+        );
+    }
+
+    #[test]
+    fn test_channel_flood_from_many_sources() {
+        let now = Instant::now();
+        let mut limiter = CtcpLimiter::new(now);
+
+        for nick in ["a", "b"] {
+            assert_eq!(limiter.check(key(nick), true, now), CtcpVerdict::Reply);
+        }
+        assert_eq!(
+            limiter.check(key("c"), true, now),
+            CtcpVerdict::Ignore {
+                notify: Some(CtcpFlood::Channel)
+            }
+        );
+        // 개인 CTCP는 채널 제한과 별개
+        assert_eq!(limiter.check(key("c"), false, now), CtcpVerdict::Reply);
+    }
+
+    #[test]
+    fn test_global_flood() {
+        let now = Instant::now();
+        let mut limiter = CtcpLimiter::new(now);
+
+        for i in 0..GLOBAL_BURST {
+            assert_eq!(
+                limiter.check(key(&format!("nick{i}")), false, now),
+                CtcpVerdict::Reply
+            );
+        }
+        assert_eq!(
+            limiter.check(key("other"), false, now),
+            CtcpVerdict::Ignore {
+                notify: Some(CtcpFlood::Global)
+            }
+        );
+    }
+}
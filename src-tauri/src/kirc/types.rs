@@ -1,5 +1,6 @@
 pub(super) mod server;
 
+use crate::kirc::types::server::Endpoint;
 use serde::Serialize;
 use std::fmt::{Display, Formatter};
 use uuid::Uuid;
@@ -16,13 +17,69 @@ pub(super) enum ServerStatus {
     Disconnected,
     Disconnecting,
     Failed,
+    AuthFailed,
+    /// 연결이 끊겨 재연결 대기 중 (next_retry_at: ms)
+    Reconnecting {
+        attempt: u32,
+        next_retry_at: u64,
+    },
+}
+
+/// 주소별 연결 실패 이유
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct EndpointFailure {
+    endpoint: Endpoint,
+    reason: String,
+}
+
+impl EndpointFailure {
+    pub(super) fn new(endpoint: Endpoint, reason: String) -> Self {
+        Self { endpoint, reason }
+    }
+}
+
+impl Display for EndpointFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.endpoint, self.reason)
+    }
 }
 
 pub(in crate::kirc) enum ServerCommand {
     Join(String),
-    Privmsg { target: String, message: String },
-    Part { channel_name: String },
+    Privmsg {
+        target: String,
+        message: String,
+    },
+    Notice {
+        target: String,
+        message: String,
+    },
+    /// CTCP 요청 (`/ctcp nick VERSION`)
+    Ctcp {
+        target: String,
+        command: String,
+        params: String,
+    },
+    /// CTCP ACTION (`/me`)
+    Action {
+        target: String,
+        message: String,
+    },
+    Part {
+        channel_name: String,
+    },
     Nick(String),
+    Topic {
+        channel: String,
+        topic: String,
+    },
+    Invite {
+        nickname: String,
+        channel: String,
+    },
+    /// 사용자가 입력한 raw IRC 줄 (perform 등)
+    Raw(String),
     Quit,
 }
 
@@ -31,8 +88,21 @@ impl Display for ServerCommand {
         match self {
             ServerCommand::Join(x) => write!(f, "Join, {x}"),
             ServerCommand::Privmsg { target, message } => write!(f, "Privmsg, {target}, {message}"),
+            ServerCommand::Notice { target, message } => write!(f, "Notice, {target}, {message}"),
+            ServerCommand::Action { target, message } => write!(f, "Action, {target}, {message}"),
+            ServerCommand::Ctcp {
+                target,
+                command,
+                params,
+            } => write!(f, "Ctcp, {target}, {command} {params}"),
             ServerCommand::Part { channel_name } => write!(f, "Part, {channel_name}"),
             ServerCommand::Nick(new_nick) => write!(f, "Nick, {new_nick}"),
+            ServerCommand::Topic { channel, topic } => write!(f, "Topic, {channel}, {topic}"),
+            ServerCommand::Invite { nickname, channel } => {
+                write!(f, "Invite, {nickname}, {channel}")
+            }
+            // 비밀번호가 포함될 수 있으므로 내용은 남기지 않음
+            ServerCommand::Raw(_) => write!(f, "Raw"),
             ServerCommand::Quit => write!(f, "Quit"),
         }
     }
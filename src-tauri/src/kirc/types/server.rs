@@ -1,4 +1,12 @@
+use crate::kirc::cap::DEFAULT_CAPABILITIES;
+use crate::kirc::ctcp::DEFAULT_VERSION;
+use crate::kirc::tls::fingerprint;
+use anyhow::{bail, Context};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub(crate) struct ServerConfig {
@@ -6,6 +14,370 @@ pub(crate) struct ServerConfig {
     port: u16,
     use_tls: bool,
     nickname: String,
+    #[serde(default)]
+    sasl: Option<SaslConfig>,
+    #[serde(default)]
+    client_cert: Option<ClientCertificate>,
+    /// 클라이언트 인증서의 SHA-256 fingerprint, 설정을 저장할 때 계산
+    #[serde(default)]
+    cert_fingerprint: Option<String>,
+    /// 등록시 요청할 IRCv3 capability (sasl은 SASL 설정에 따라 자동으로 요청)
+    #[serde(default = "default_capabilities")]
+    capabilities: Vec<String>,
+    /// 등록 중 닉네임이 사용중일 때 순서대로 시도할 닉네임
+    #[serde(default)]
+    alt_nicks: Vec<String>,
+    #[serde(default)]
+    services: Option<ServicesConfig>,
+    /// 등록 전에 보낼 서버 비밀번호 (PASS)
+    #[serde(default)]
+    password: Option<String>,
+    /// RPL_WELCOME 이후 채널 재입장 전에 순서대로 보낼 명령
+    #[serde(default)]
+    perform: Vec<PerformCommand>,
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+    #[serde(default)]
+    tls: TlsOptions,
+    #[serde(default)]
+    auto_connect: AutoConnect,
+    /// 기본 주소 연결에 실패하면 순서대로 시도할 주소
+    #[serde(default)]
+    fallback_endpoints: Vec<Endpoint>,
+    #[serde(default)]
+    ctcp: CtcpConfig,
+    #[serde(default)]
+    send_rate: SendRate,
+}
+
+/// 서버로 보내는 메세지 속도 제한, excess flood로 끊기지 않도록 함
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct SendRate {
+    /// 기다리지 않고 연속으로 보낼 수 있는 줄 수
+    burst: u32,
+    /// 이후 한 줄씩 보내는 간격 (ms)
+    interval_ms: u64,
+}
+
+impl Default for SendRate {
+    fn default() -> Self {
+        Self {
+            burst: 5,
+            interval_ms: 2_000,
+        }
+    }
+}
+
+impl SendRate {
+    pub(in crate::kirc) fn burst(&self) -> u32 {
+        self.burst.max(1)
+    }
+
+    pub(in crate::kirc) fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// 받은 CTCP 요청에 대한 응답 설정
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub(crate) struct CtcpConfig {
+    /// VERSION 응답, 없으면 기본 버전 문자열
+    #[serde(default)]
+    version: Option<String>,
+    /// USERINFO 응답, 없으면 현재 닉네임
+    #[serde(default)]
+    userinfo: Option<String>,
+    /// FINGER 응답, 없으면 현재 닉네임
+    #[serde(default)]
+    finger: Option<String>,
+    /// 응답하지 않을 CTCP 명령 (VERSION, TIME 등)
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+impl CtcpConfig {
+    pub(in crate::kirc) fn version(&self) -> &str {
+        self.version.as_deref().unwrap_or(DEFAULT_VERSION)
+    }
+
+    pub(in crate::kirc) fn userinfo(&self) -> Option<&str> {
+        self.userinfo.as_deref()
+    }
+
+    pub(in crate::kirc) fn finger(&self) -> Option<&str> {
+        self.finger.as_deref()
+    }
+
+    pub(in crate::kirc) fn is_enabled(&self, command: &str) -> bool {
+        !self
+            .disabled
+            .iter()
+            .any(|disabled| disabled.eq_ignore_ascii_case(command))
+    }
+}
+
+/// 같은 네트워크에 연결할 수 있는 서버 주소
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct Endpoint {
+    server: String,
+    port: u16,
+    #[serde(default)]
+    use_tls: bool,
+}
+
+impl Endpoint {
+    pub(in crate::kirc) fn new(server: String, port: u16, use_tls: bool) -> Self {
+        Self {
+            server,
+            port,
+            use_tls,
+        }
+    }
+
+    pub(in crate::kirc) fn server(&self) -> &str {
+        &self.server
+    }
+
+    pub(in crate::kirc) fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub(in crate::kirc) fn use_tls(&self) -> bool {
+        self.use_tls
+    }
+
+    /// 인증서 고정에 쓰는 `host:port`
+    fn address(&self) -> String {
+        format!("{}:{}", self.server, self.port)
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.use_tls {
+            write!(f, "{}:+{}", self.server, self.port)
+        } else {
+            write!(f, "{}:{}", self.server, self.port)
+        }
+    }
+}
+
+/// 앱 시작시 자동 연결 정책
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AutoConnect {
+    #[default]
+    Always,
+    /// 지난번 종료할 때 연결되어 있던 경우에만
+    LastSession,
+    Never,
+}
+
+fn default_capabilities() -> Vec<String> {
+    DEFAULT_CAPABILITIES
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// SASL 인증 설정 (CAP 협상 중 등록 완료 전에 인증)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(tag = "mechanism")]
+pub(crate) enum SaslConfig {
+    Plain {
+        account: String,
+        password: String,
+    },
+    /// TLS 핸드셰이크에서 제시한 클라이언트 인증서로 인증 (CertFP)
+    External,
+}
+
+/// 접속 후 실행할 raw IRC 명령. `$me`는 현재 닉네임으로 치환
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct PerformCommand {
+    line: String,
+    /// 이전 명령을 보낸 뒤 기다릴 시간 (ms)
+    #[serde(default)]
+    delay_ms: u64,
+}
+
+impl PerformCommand {
+    /// `$me`를 현재 닉네임으로 바꾸고 `/msg` 등 클라이언트 명령 형태면 IRC 명령으로 변환
+    ///
+    /// `/`만 떼고 보내면 `MSG NickServ IDENTIFY ...`처럼 알 수 없는 명령으로 비밀번호가 전송됨
+    pub(in crate::kirc) fn irc_line(&self, nickname: &str) -> anyhow::Result<String> {
+        let line = self.line.replace("$me", nickname);
+        let Some(command) = line.strip_prefix('/') else {
+            return Ok(line);
+        };
+
+        let (name, rest) = command.split_once(' ').unwrap_or((command, ""));
+        let rest = rest.trim_start();
+        Ok(match name.to_ascii_lowercase().as_str() {
+            name @ ("msg" | "notice") => {
+                let Some((target, text)) = rest
+                    .split_once(' ')
+                    .filter(|(_, text)| !text.trim().is_empty())
+                else {
+                    bail!("/{name} requires a target and a message");
+                };
+                let command = if name == "msg" { "PRIVMSG" } else { "NOTICE" };
+                format!("{command} {target} :{}", text.trim_start())
+            }
+            "quote" | "raw" => rest.to_string(),
+            "me" => bail!("/me can't be used in perform, use PRIVMSG with ACTION instead"),
+            _ => command.to_string(),
+        })
+    }
+
+    pub(in crate::kirc) fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+}
+
+/// 서버 인증서 신뢰 설정 (use_tls일 때만 사용)
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub(crate) struct TlsOptions {
+    /// 시스템 인증서 외에 신뢰할 CA 번들 (PEM)
+    #[serde(default)]
+    ca_bundle: Option<PathBuf>,
+    /// 주소(`host:port`)별로 고정된 서버 인증서 SHA-256 fingerprint
+    ///
+    /// CA 검증에 실패한 인증서도 일치하면 신뢰
+    #[serde(default)]
+    pinned_fingerprints: BTreeMap<String, String>,
+    /// 고정된 fingerprint가 없으면 첫 연결에서 사용자 승인을 받아 고정
+    #[serde(default)]
+    trust_on_first_use: bool,
+}
+
+impl TlsOptions {
+    pub(in crate::kirc) fn ca_bundle(&self) -> Option<&Path> {
+        self.ca_bundle.as_deref()
+    }
+
+    pub(in crate::kirc) fn trust_on_first_use(&self) -> bool {
+        self.trust_on_first_use
+    }
+}
+
+/// 서버 연결에 사용할 프록시
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ProxyConfig {
+    #[serde(rename = "type")]
+    kind: ProxyKind,
+    host: String,
+    port: u16,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ProxyKind {
+    Socks5,
+    /// HTTP CONNECT 터널
+    Http,
+}
+
+impl ProxyConfig {
+    pub(in crate::kirc) fn kind(&self) -> ProxyKind {
+        self.kind
+    }
+
+    pub(in crate::kirc) fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub(in crate::kirc) fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub(in crate::kirc) fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// username이 설정된 경우에만 (username, password)
+    pub(in crate::kirc) fn credentials(&self) -> Option<(&str, &str)> {
+        self.username
+            .as_deref()
+            .map(|username| (username, self.password.as_deref().unwrap_or_default()))
+    }
+}
+
+/// NickServ 설정 (alternate 닉네임으로 접속했을 때 기본 닉네임 회수)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ServicesConfig {
+    #[serde(default = "default_nickserv")]
+    nickserv: String,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    recovery: NickRecoveryMethod,
+}
+
+fn default_nickserv() -> String {
+    "NickServ".to_string()
+}
+
+/// 기본 닉네임을 점유한 세션을 처리하는 NickServ 명령
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub(crate) enum NickRecoveryMethod {
+    /// 점유 세션을 끊고 닉네임을 바로 바꿔줌 (Atheme)
+    #[default]
+    Regain,
+    /// 점유 세션만 끊음, 이후 직접 NICK 전송
+    Ghost,
+    /// 점유 세션을 끊고 닉네임을 바꿔줌 (Anope)
+    Recover,
+}
+
+impl ServicesConfig {
+    pub(in crate::kirc) fn nickserv(&self) -> &str {
+        &self.nickserv
+    }
+
+    pub(in crate::kirc) fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    pub(in crate::kirc) fn recovery(&self) -> NickRecoveryMethod {
+        self.recovery
+    }
+}
+
+/// TLS 핸드셰이크에서 제시할 클라이언트 인증서
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(tag = "format")]
+pub(crate) enum ClientCertificate {
+    /// 인증서 PEM + PKCS#8 개인키 PEM
+    Pem {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+    Pkcs12 {
+        path: PathBuf,
+        password: String,
+    },
+}
+
+impl SaslConfig {
+    pub(in crate::kirc) fn mechanism(&self) -> &'static str {
+        match self {
+            SaslConfig::Plain { .. } => "PLAIN",
+            SaslConfig::External => "EXTERNAL",
+        }
+    }
+
+    pub(in crate::kirc) fn account(&self) -> Option<&str> {
+        match self {
+            SaslConfig::Plain { account, .. } => Some(account),
+            SaslConfig::External => None,
+        }
+    }
 }
 
 impl ServerConfig {
@@ -15,9 +387,112 @@ impl ServerConfig {
             port,
             use_tls,
             nickname,
+            sasl: None,
+            client_cert: None,
+            cert_fingerprint: None,
+            capabilities: default_capabilities(),
+            alt_nicks: Vec::new(),
+            services: None,
+            password: None,
+            perform: Vec::new(),
+            proxy: None,
+            tls: TlsOptions::default(),
+            auto_connect: AutoConnect::default(),
+            fallback_endpoints: Vec::new(),
+            ctcp: CtcpConfig::default(),
+            send_rate: SendRate::default(),
         }
     }
 
+    pub(in crate::kirc) fn with_server(mut self, server: String) -> Self {
+        self.server = server;
+        self
+    }
+
+    pub(in crate::kirc) fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub(in crate::kirc) fn with_use_tls(mut self, use_tls: bool) -> Self {
+        self.use_tls = use_tls;
+        self
+    }
+
+    pub(in crate::kirc) fn with_nickname(mut self, nickname: String) -> Self {
+        self.nickname = nickname;
+        self
+    }
+
+    pub(in crate::kirc) fn with_sasl(mut self, sasl: Option<SaslConfig>) -> Self {
+        self.sasl = sasl;
+        self
+    }
+
+    pub(in crate::kirc) fn with_client_cert(
+        mut self,
+        client_cert: Option<ClientCertificate>,
+    ) -> Self {
+        self.client_cert = client_cert;
+        self.cert_fingerprint = None;
+        self
+    }
+
+    pub(in crate::kirc) fn with_capabilities(mut self, capabilities: Vec<String>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub(in crate::kirc) fn with_alt_nicks(mut self, alt_nicks: Vec<String>) -> Self {
+        self.alt_nicks = alt_nicks;
+        self
+    }
+
+    pub(in crate::kirc) fn with_services(mut self, services: Option<ServicesConfig>) -> Self {
+        self.services = services;
+        self
+    }
+
+    pub(in crate::kirc) fn with_password(mut self, password: Option<String>) -> Self {
+        self.password = password;
+        self
+    }
+
+    pub(in crate::kirc) fn with_perform(mut self, perform: Vec<PerformCommand>) -> Self {
+        self.perform = perform;
+        self
+    }
+
+    pub(in crate::kirc) fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    pub(in crate::kirc) fn with_tls_options(mut self, tls: TlsOptions) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub(in crate::kirc) fn with_auto_connect(mut self, auto_connect: AutoConnect) -> Self {
+        self.auto_connect = auto_connect;
+        self
+    }
+
+    pub(in crate::kirc) fn with_fallback_endpoints(mut self, endpoints: Vec<Endpoint>) -> Self {
+        self.fallback_endpoints = endpoints;
+        self
+    }
+
+    pub(in crate::kirc) fn with_ctcp(mut self, ctcp: CtcpConfig) -> Self {
+        self.ctcp = ctcp;
+        self
+    }
+
+    pub(in crate::kirc) fn with_send_rate(mut self, send_rate: SendRate) -> Self {
+        self.send_rate = send_rate;
+        self
+    }
+
     pub(in crate::kirc) fn server(&self) -> &str {
         &self.server
     }
@@ -33,4 +508,267 @@ impl ServerConfig {
     pub(in crate::kirc) fn nickname(&self) -> &str {
         &self.nickname
     }
+
+    pub(in crate::kirc) fn sasl(&self) -> Option<&SaslConfig> {
+        self.sasl.as_ref()
+    }
+
+    pub(in crate::kirc) fn client_cert(&self) -> Option<&ClientCertificate> {
+        self.client_cert.as_ref()
+    }
+
+    pub(in crate::kirc) fn cert_fingerprint(&self) -> Option<&str> {
+        self.cert_fingerprint.as_deref()
+    }
+
+    pub(in crate::kirc) fn capabilities(&self) -> &[String] {
+        &self.capabilities
+    }
+
+    pub(in crate::kirc) fn alt_nicks(&self) -> &[String] {
+        &self.alt_nicks
+    }
+
+    pub(in crate::kirc) fn services(&self) -> Option<&ServicesConfig> {
+        self.services.as_ref()
+    }
+
+    pub(in crate::kirc) fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    pub(in crate::kirc) fn perform(&self) -> &[PerformCommand] {
+        &self.perform
+    }
+
+    pub(in crate::kirc) fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    pub(in crate::kirc) fn tls_options(&self) -> &TlsOptions {
+        &self.tls
+    }
+
+    /// 시도할 주소 목록, 기본 주소가 처음
+    pub(in crate::kirc) fn endpoints(&self) -> Vec<Endpoint> {
+        let primary = Endpoint::new(self.server.clone(), self.port, self.use_tls);
+
+        std::iter::once(primary)
+            .chain(self.fallback_endpoints.iter().cloned())
+            .collect()
+    }
+
+    pub(in crate::kirc) fn ctcp(&self) -> &CtcpConfig {
+        &self.ctcp
+    }
+
+    pub(in crate::kirc) fn send_rate(&self) -> SendRate {
+        self.send_rate
+    }
+
+    pub(in crate::kirc) fn auto_connect(&self) -> AutoConnect {
+        self.auto_connect
+    }
+
+    /// 저장 전에 설정을 검증하고 클라이언트 인증서를 읽어 fingerprint 계산
+    pub(in crate::kirc) fn validated(mut self) -> anyhow::Result<Self> {
+        if matches!(self.sasl, Some(SaslConfig::External)) && self.client_cert.is_none() {
+            bail!("SASL EXTERNAL requires a client certificate");
+        }
+
+        for command in &self.perform {
+            command
+                .irc_line(&self.nickname)
+                .with_context(|| format!("Invalid perform command: {}", command.line))?;
+        }
+
+        self.cert_fingerprint = self
+            .client_cert
+            .as_ref()
+            .map(fingerprint)
+            .transpose()
+            .context("Can't load client certificate")?;
+
+        Ok(self)
+    }
+
+    /// 주소에 고정된 서버 인증서 fingerprint
+    pub(in crate::kirc) fn pinned_fingerprint(&self, endpoint: &Endpoint) -> Option<&str> {
+        self.tls
+            .pinned_fingerprints
+            .get(&endpoint.address())
+            .map(String::as_str)
+    }
+
+    /// TOFU로 승인한 서버 인증서 fingerprint를 주소별로 고정
+    pub(in crate::kirc) fn pin_tls_fingerprint(
+        &mut self,
+        endpoint: &Endpoint,
+        fingerprint: String,
+    ) {
+        self.tls
+            .pinned_fingerprints
+            .insert(endpoint.address(), fingerprint);
+    }
+
+    /// 기본 닉네임 다음으로 시도할 닉네임 목록
+    ///
+    /// 설정된 alt nick 뒤에 `nick_`, `nick__`, `nick1`을 붙이고 중복은 제거
+    pub(in crate::kirc) fn fallback_nicknames(&self) -> Vec<String> {
+        let generated = [
+            format!("{}_", self.nickname),
+            format!("{}__", self.nickname),
+            format!("{}1", self.nickname),
+        ];
+
+        let mut nicks: Vec<String> = Vec::new();
+        for nick in self.alt_nicks().iter().cloned().chain(generated) {
+            if !nick.is_empty() && nick != self.nickname && !nicks.contains(&nick) {
+                nicks.push(nick);
+            }
+        }
+        nicks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(nickname: &str) -> ServerConfig {
+        ServerConfig::new(
+            "irc.example.com".to_string(),
+            6697,
+            true,
+            nickname.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_fallback_nicknames_generated() {
+        assert_eq!(
+            config("kirc").fallback_nicknames(),
+            vec!["kirc_", "kirc__", "kirc1"]
+        );
+    }
+
+    #[test]
+    fn test_fallback_nicknames_alt_first() {
+        let config = config("kirc").with_alt_nicks(vec![
+            "kirc_away".to_string(),
+            "kirc".to_string(),
+            "kirc_".to_string(),
+            "".to_string(),
+        ]);
+
+        assert_eq!(
+            config.fallback_nicknames(),
+            vec!["kirc_away", "kirc_", "kirc__", "kirc1"]
+        );
+    }
+
+    #[test]
+    fn test_config_without_alt_nicks_deserializes() {
+        let config: ServerConfig = serde_json::from_str(
+            r#"{"server":"irc.example.com","port":6667,"use_tls":false,"nickname":"kirc"}"#,
+        )
+        .unwrap();
+
+        assert!(config.alt_nicks().is_empty());
+        assert!(config.services().is_none());
+        assert!(config.password().is_none());
+        assert!(config.perform().is_empty());
+        assert!(config.proxy().is_none());
+        assert!(config.pinned_fingerprint(&config.endpoints()[0]).is_none());
+        assert!(!config.tls_options().trust_on_first_use());
+    }
+
+    #[test]
+    fn test_sasl_external_requires_client_cert() {
+        let config = config("kirc").with_sasl(Some(SaslConfig::External));
+
+        assert!(config.validated().is_err());
+    }
+
+    #[test]
+    fn test_validated_reports_missing_client_cert_file() {
+        let config = config("kirc").with_client_cert(Some(ClientCertificate::Pem {
+            cert_path: PathBuf::from("/nonexistent/kirc.pem"),
+            key_path: PathBuf::from("/nonexistent/kirc.key"),
+        }));
+
+        assert!(config.validated().is_err());
+    }
+
+    #[test]
+    fn test_perform_command_delay_default() {
+        let command: PerformCommand = serde_json::from_str(r#"{"line":"MODE $me +x"}"#).unwrap();
+
+        assert_eq!(command.irc_line("kirc").unwrap(), "MODE kirc +x");
+        assert_eq!(command.delay(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_perform_command_client_aliases() {
+        let irc_line = |line: &str| {
+            PerformCommand {
+                line: line.to_string(),
+                delay_ms: 0,
+            }
+            .irc_line("kirc")
+        };
+
+        assert_eq!(
+            irc_line("/msg NickServ IDENTIFY pw").unwrap(),
+            "PRIVMSG NickServ :IDENTIFY pw"
+        );
+        assert_eq!(irc_line("/NOTICE bob hi").unwrap(), "NOTICE bob :hi");
+        assert_eq!(irc_line("/quote OPER kirc pw").unwrap(), "OPER kirc pw");
+        assert_eq!(irc_line("/join #kirc").unwrap(), "join #kirc");
+        assert!(irc_line("/msg NickServ").is_err());
+        assert!(irc_line("/me waves").is_err());
+
+        let config = config("kirc").with_perform(vec![PerformCommand {
+            line: "/me waves".to_string(),
+            delay_ms: 0,
+        }]);
+        assert!(config.validated().is_err());
+    }
+
+    #[test]
+    fn test_endpoints_primary_first() {
+        let config = config("kirc").with_fallback_endpoints(vec![Endpoint::new(
+            "irc2.example.com".to_string(),
+            6667,
+            false,
+        )]);
+
+        let endpoints: Vec<String> = config.endpoints().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            endpoints,
+            vec!["irc.example.com:+6697", "irc2.example.com:6667"]
+        );
+    }
+
+    #[test]
+    fn test_pinned_fingerprint_per_endpoint() {
+        let fallback = Endpoint::new("irc2.example.com".to_string(), 6697, true);
+        let mut config = config("kirc").with_fallback_endpoints(vec![fallback.clone()]);
+        let primary = config.endpoints()[0].clone();
+
+        config.pin_tls_fingerprint(&fallback, "bb".to_string());
+        assert_eq!(config.pinned_fingerprint(&primary), None);
+        config.pin_tls_fingerprint(&primary, "cc".to_string());
+        assert_eq!(config.pinned_fingerprint(&fallback), Some("bb"));
+        assert_eq!(config.pinned_fingerprint(&primary), Some("cc"));
+    }
+
+    #[test]
+    fn test_services_defaults() {
+        let services: ServicesConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(services.nickserv(), "NickServ");
+        assert_eq!(services.password(), None);
+        assert_eq!(services.recovery(), NickRecoveryMethod::Regain);
+    }
 }
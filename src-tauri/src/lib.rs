@@ -54,9 +54,16 @@ pub fn run() {
             }
             let config_path = app_data_dir.join("config.json");
             let snapshot: KircStateSnapshot = fs::load(&config_path).unwrap();
+            let needs_migration = snapshot.needs_migration();
 
             let mut state = snapshot.restore();
             state.set_persistence_path(&config_path);
+            if needs_migration {
+                // 새로 발급한 ServerId를 바로 저장해 다음 실행에도 유지
+                if let Err(e) = state.save_snapshot() {
+                    warn!("Failed to migrate config: {e}");
+                }
+            }
             let state = Arc::new(state);
 
             app.manage(KircManager::new(state.clone(), app.handle().clone()));
@@ -78,7 +85,26 @@ pub fn run() {
             kirc::commands::lock_channel,
             kirc::commands::unlock_channel,
             kirc::commands::is_channel_locked,
-            kirc::commands::change_nickname
+            kirc::commands::change_nickname,
+            kirc::commands::accept_tls_certificate,
+            kirc::commands::reject_tls_certificate,
+            kirc::commands::remove_server,
+            kirc::commands::update_server_config,
+            kirc::commands::get_server_features,
+            kirc::commands::get_channel_users,
+            kirc::commands::set_topic,
+            kirc::commands::send_notice,
+            kirc::commands::send_action,
+            kirc::commands::send_ctcp,
+            kirc::commands::get_ctcp_config,
+            kirc::commands::set_ctcp_config,
+            kirc::commands::cancel_pending_messages,
+            kirc::commands::set_send_rate,
+            kirc::commands::open_query,
+            kirc::commands::close_query,
+            kirc::commands::accept_invite,
+            kirc::commands::send_invite,
+            kirc::commands::set_channel_rejoin_after_kick
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
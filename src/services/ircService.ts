@@ -1,7 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { ircStore } from "../stores/irc.svelte";
-import { SvelteSet } from "svelte/reactivity";
+import { SvelteMap } from "svelte/reactivity";
 import {
   type ChannelId,
   type ChatMessage,
@@ -13,6 +13,12 @@ import {
 import type {
   ChangeNickFailedPayload,
   ChannelLockChangedEvent,
+  MembersChangedPayload,
+  ModeChange,
+  QueryChangedPayload,
+  SendQueuePayload,
+  ServerFeaturesPayload,
+  TlsUntrustedPayload,
   UiEventPayload,
 } from "../types/payloads.svelte";
 
@@ -26,6 +32,8 @@ export class IrcService {
         ...s,
         serverMessages: [],
       });
+      s.queries.forEach((nick: string) => this.ensureChannel(s.id, nick, true));
+      ircStore.sendQueue.set(s.id, s.pendingMessages);
     });
 
     await this.setupEventListeners();
@@ -37,18 +45,80 @@ export class IrcService {
 
       switch (payload.type) {
         case "UserMessage": {
-          this.ensureChannel(payload.server_id, payload.channel);
-          this.addMessage(
-            payload.server_id,
-            this.getChannelId(payload.server_id, payload.channel),
-            {
-              type: MessageType.USER,
-              id: crypto.randomUUID(),
-              nickname: payload.nick,
-              content: payload.content,
-              timestamp: payload.timestamp,
-            },
-          );
+          const message: ChatMessage = {
+            type: MessageType.USER,
+            id: crypto.randomUUID(),
+            nickname: payload.nick,
+            content: payload.content,
+            timestamp: payload.timestamp,
+          };
+
+          // 닉네임 없이 서버가 보낸 메세지는 서버 버퍼
+          if (payload.channel) {
+            this.ensureChannel(payload.server_id, payload.channel);
+            this.addMessage(
+              payload.server_id,
+              this.getChannelId(payload.server_id, payload.channel),
+              message,
+            );
+          } else {
+            this.addServerMessage(payload.server_id, message);
+          }
+          break;
+        }
+        case "Action": {
+          const message: ChatMessage = {
+            type: MessageType.ACTION,
+            id: crypto.randomUUID(),
+            nickname: payload.nick,
+            content: payload.content,
+            timestamp: payload.timestamp,
+          };
+
+          // 닉네임 없이 서버가 보낸 메세지는 서버 버퍼
+          if (payload.channel) {
+            this.ensureChannel(payload.server_id, payload.channel);
+            this.addMessage(
+              payload.server_id,
+              this.getChannelId(payload.server_id, payload.channel),
+              message,
+            );
+          } else {
+            this.addServerMessage(payload.server_id, message);
+          }
+          break;
+        }
+        case "Notice": {
+          const message: ChatMessage = {
+            type: MessageType.SYSTEM,
+            id: crypto.randomUUID(),
+            content: `-${payload.nick}- ${payload.content}`,
+            timestamp: payload.timestamp,
+          };
+
+          // 채널 notice가 아니면 서버 버퍼
+          if (payload.channel) {
+            this.ensureChannel(payload.server_id, payload.channel);
+            this.addMessage(
+              payload.server_id,
+              this.getChannelId(payload.server_id, payload.channel),
+              message,
+            );
+          } else {
+            this.addServerMessage(payload.server_id, message);
+          }
+          break;
+        }
+        case "CtcpReply": {
+          this.addServerMessage(payload.server_id, {
+            type: MessageType.SYSTEM,
+            id: crypto.randomUUID(),
+            content:
+              payload.rtt_ms !== undefined && payload.rtt_ms !== null
+                ? `CTCP PING reply from ${payload.nick}: ${payload.rtt_ms}ms`
+                : `CTCP ${payload.command} reply from ${payload.nick}: ${payload.params}`,
+            timestamp: Date.now(),
+          });
           break;
         }
         case "Join": {
@@ -57,8 +127,6 @@ export class IrcService {
           const channelId = this.getChannelId(payload.server_id, payload.channel);
           const channel = ircStore.channels.get(channelId);
           if (server && channel) {
-            channel.users.add(payload.nick);
-
             if (payload.nick === server.nickname) {
               ircStore.currentServerId = payload.server_id;
               ircStore.currentChannelId = channelId;
@@ -87,8 +155,6 @@ export class IrcService {
               // 다른사람이 나간 경우
               const channel = ircStore.channels.get(channelId);
               if (channel) {
-                channel.users.delete(payload.nick);
-
                 this.addMessage(payload.server_id, channelId, {
                   type: MessageType.SYSTEM,
                   id: crypto.randomUUID(),
@@ -100,13 +166,47 @@ export class IrcService {
           }
           break;
         }
+        case "Kick": {
+          const server = ircStore.servers.get(payload.server_id);
+          const channelId = this.getChannelId(payload.server_id, payload.channel);
+          const channel = ircStore.channels.get(channelId);
+          if (server && channel) {
+            const reason = payload.reason ? ` (${payload.reason})` : "";
+            const kickedMe = payload.nick === server.nickname;
+            this.addMessage(payload.server_id, channelId, {
+              type: MessageType.SYSTEM,
+              id: crypto.randomUUID(),
+              content: kickedMe
+                ? `You were kicked from ${payload.channel} by ${payload.by}${reason}`
+                : `${payload.nick} was kicked by ${payload.by}${reason}`,
+              timestamp: Date.now(),
+            });
+            if (kickedMe) {
+              // 다시 들어오면 NAMES로 새로 받음
+              channel.users.clear();
+            }
+          }
+          break;
+        }
+        case "Invite": {
+          const { server_id: serverId, channel, by } = payload;
+          this.addServerMessage(serverId, {
+            type: MessageType.SYSTEM,
+            id: crypto.randomUUID(),
+            content: `${by} invited you to ${channel}`,
+            timestamp: Date.now(),
+          });
+
+          if (confirm(`${by}님이 ${channel} 채널에 초대했습니다. 들어가시겠습니까?`)) {
+            void invoke("accept_invite", { serverId, channel });
+          }
+          break;
+        }
         case "Quit": {
           const server = ircStore.servers.get(payload.server_id);
           if (server) {
             for (const channel of ircStore.channels.values()) {
               if (channel.serverId === payload.server_id && channel.users.has(payload.nick)) {
-                channel.users.delete(payload.nick);
-
                 this.addMessage(
                   payload.server_id,
                   this.getChannelId(payload.server_id, channel.name),
@@ -137,9 +237,6 @@ export class IrcService {
             }
             for (const channel of ircStore.channels.values()) {
               if (channel.serverId === payload.server_id && channel.users.has(payload.old_nick)) {
-                channel.users.delete(payload.old_nick);
-                channel.users.add(payload.new_nick);
-
                 this.addMessage(
                   payload.server_id,
                   this.getChannelId(payload.server_id, channel.name),
@@ -163,17 +260,46 @@ export class IrcService {
             const channel = ircStore.channels.get(channelId);
             if (channel) {
               channel.topic = payload.topic;
+              channel.topicSetBy = payload.set_by;
+              channel.topicSetAt = payload.set_at;
+
+              let content: string;
+              if (payload.on_join) {
+                const setAt = payload.set_at ? new Date(payload.set_at).toLocaleString() : "";
+                content = `Topic: ${payload.topic} (set by ${payload.set_by ?? "unknown"}${setAt ? `, ${setAt}` : ""})`;
+              } else if (payload.topic) {
+                content = `${payload.set_by} changed the topic to: ${payload.topic}`;
+              } else {
+                content = `${payload.set_by} cleared the topic`;
+              }
 
-              this.addMessage(payload.server_id, channel.name, {
+              this.addMessage(payload.server_id, channelId, {
                 type: MessageType.SYSTEM,
                 id: crypto.randomUUID(),
-                content: `Topic set to: ${payload.topic}`,
-                timestamp: Date.now(),
+                content,
+                timestamp: payload.set_at ?? Date.now(),
               });
             }
           }
           break;
         }
+        case "Mode": {
+          const channelId = this.getChannelId(payload.server_id, payload.target);
+          const message: ChatMessage = {
+            type: MessageType.SYSTEM,
+            id: crypto.randomUUID(),
+            content: `${payload.setter} sets mode ${this.formatModeChanges(payload.changes)} on ${payload.target}`,
+            timestamp: Date.now(),
+          };
+
+          // 채널이 아니면 자신의 유저 모드
+          if (ircStore.channels.has(channelId)) {
+            this.addMessage(payload.server_id, channelId, message);
+          } else {
+            this.addServerMessage(payload.server_id, message);
+          }
+          break;
+        }
         case "Error": {
           this.addServerMessage(payload.server_id, {
             type: MessageType.SYSTEM,
@@ -190,20 +316,37 @@ export class IrcService {
       const { serverId, status } = event.payload;
       const server = ircStore.servers.get(serverId);
       if (server) {
+        const name: string = typeof status === "string" ? status : Object.keys(status)[0];
         ircStore.servers.set(serverId, {
           ...server,
-          status: status.toLowerCase() as IrcServerStatus,
+          status: name.toLowerCase() as IrcServerStatus,
         });
       }
     });
 
+    await listen<any>("kirc:nick_recovered", (event) => {
+      const { serverId, nickname } = event.payload;
+      const server = ircStore.servers.get(serverId);
+      if (server) {
+        ircStore.servers.set(serverId, { ...server, nickname });
+      }
+    });
+
+    await listen<any>("kirc:nickname_acquired", (event) => {
+      const { serverId, nickname } = event.payload;
+      const server = ircStore.servers.get(serverId);
+      if (server) {
+        ircStore.servers.set(serverId, { ...server, nickname });
+      }
+    });
+
     await listen<any>("kirc:server_added", (event) => {
       const payload = event.payload;
       if (ircStore.servers.has(payload.serverId)) return;
 
       ircStore.servers.set(payload.serverId, {
         id: payload.serverId,
-        name: payload.host,
+        name: ircStore.serverFeatures.get(payload.serverId)?.network ?? payload.host,
         host: payload.host,
         port: payload.port,
         tls: payload.tls,
@@ -213,6 +356,122 @@ export class IrcService {
       });
     });
 
+    await listen<any>("kirc:server_updated", (event) => {
+      const payload = event.payload;
+      const server = ircStore.servers.get(payload.serverId);
+      if (!server) return;
+
+      ircStore.servers.set(payload.serverId, {
+        ...server,
+        name: ircStore.serverFeatures.get(payload.serverId)?.network ?? payload.host,
+        host: payload.host,
+        port: payload.port,
+        tls: payload.tls,
+        nickname: payload.nickname,
+      });
+    });
+
+    // 멤버 목록은 백엔드가 관리, Quit/Nick 메세지는 이 이벤트보다 먼저 도착
+    await listen<MembersChangedPayload>("kirc:members_changed", (event) => {
+      const { serverId, channel: channelName, change } = event.payload;
+      const channel = ircStore.channels.get(this.getChannelId(serverId, channelName));
+      if (!channel) return;
+
+      switch (change.type) {
+        case "Reset": {
+          channel.users.clear();
+          for (const user of change.users) {
+            channel.users.set(user.nick, user.prefix);
+          }
+          break;
+        }
+        case "Joined":
+        case "Updated": {
+          channel.users.set(change.user.nick, change.user.prefix);
+          break;
+        }
+        case "Left": {
+          channel.users.delete(change.nick);
+          break;
+        }
+        case "Renamed": {
+          channel.users.delete(change.oldNick);
+          channel.users.set(change.user.nick, change.user.prefix);
+          break;
+        }
+      }
+    });
+
+    await listen<QueryChangedPayload>("kirc:query_changed", (event) => {
+      const { serverId, change } = event.payload;
+
+      switch (change.type) {
+        case "Opened": {
+          this.ensureChannel(serverId, change.nick, true);
+          break;
+        }
+        case "Closed": {
+          const channelId = this.getChannelId(serverId, change.nick);
+          if (ircStore.currentChannelId === channelId) {
+            ircStore.currentChannelId = null;
+          }
+          ircStore.channels.delete(channelId);
+          ircStore.messages.delete(channelId);
+          break;
+        }
+        case "Renamed": {
+          // 대화 버퍼를 새 닉네임으로 옮김
+          const oldId = this.getChannelId(serverId, change.oldNick);
+          const newId = this.getChannelId(serverId, change.nick);
+          const channel = ircStore.channels.get(oldId);
+          if (!channel) break;
+
+          ircStore.channels.delete(oldId);
+          ircStore.channels.set(newId, { ...channel, name: change.nick });
+          ircStore.messages.set(newId, ircStore.messages.get(oldId) ?? []);
+          ircStore.messages.delete(oldId);
+          if (ircStore.currentChannelId === oldId) {
+            ircStore.currentChannelId = newId;
+          }
+          break;
+        }
+      }
+    });
+
+    await listen<ServerFeaturesPayload>("kirc:server_features", (event) => {
+      const { serverId, name, features } = event.payload;
+      ircStore.serverFeatures.set(serverId, features);
+
+      const server = ircStore.servers.get(serverId);
+      if (!server) return;
+
+      ircStore.servers.set(serverId, { ...server, name });
+    });
+
+    await listen<SendQueuePayload>("kirc:send_queue", (event) => {
+      const { serverId, depth } = event.payload;
+      ircStore.sendQueue.set(serverId, depth);
+    });
+
+    await listen<any>("kirc:server_removed", (event) => {
+      const { serverId } = event.payload;
+      ircStore.servers.delete(serverId);
+      ircStore.nickErrors.delete(serverId);
+      ircStore.serverFeatures.delete(serverId);
+      ircStore.sendQueue.delete(serverId);
+
+      for (const [channelId, channel] of ircStore.channels) {
+        if (channel.serverId !== serverId) continue;
+        ircStore.channels.delete(channelId);
+        ircStore.messages.delete(channelId);
+      }
+
+      if (ircStore.currentServerId === serverId) {
+        ircStore.currentServerId = null;
+        ircStore.currentChannelId = null;
+      }
+    });
+
     await listen<ChannelLockChangedEvent>("kirc:channel_lock_changed", (event) => {
       const { channel, locked } = event.payload;
       this.updateChannelLock(channel, locked);
@@ -222,9 +481,20 @@ export class IrcService {
       const { serverId, reason } = event.payload;
       ircStore.nickErrors.set(serverId, reason);
     });
+
+    await listen<TlsUntrustedPayload>("kirc:tls_untrusted", async (event) => {
+      const { serverId, endpoint, fingerprint } = event.payload;
+      const accepted = confirm(`${endpoint} 서버 인증서를 신뢰하시겠습니까?\n\nSHA-256: ${fingerprint}`);
+
+      if (accepted) {
+        await invoke("accept_tls_certificate", { serverId, fingerprint });
+      } else {
+        await invoke("reject_tls_certificate", { serverId });
+      }
+    });
   }
 
-  ensureChannel(serverId: ServerId, channelName: string) {
+  ensureChannel(serverId: ServerId, channelName: string, isQuery = false) {
     const server = ircStore.servers.get(serverId);
     if (!server) return;
     let channelId = this.getChannelId(serverId, channelName);
@@ -233,9 +503,10 @@ export class IrcService {
       ircStore.channels.set(channelId, {
         serverId,
         name: channelName,
-        users: new SvelteSet(),
+        users: new SvelteMap(),
         unread: 0,
         locked: false,
+        isQuery,
       });
     }
 
@@ -255,7 +526,7 @@ export class IrcService {
 
     const isCurrent =
       ircStore.currentServerId === serverId && ircStore.currentChannelId === channelId;
-    if (!isCurrent && message.type === MessageType.USER) {
+    if (!isCurrent && message.type !== MessageType.SYSTEM) {
       ircStore.channels.set(channelId, { ...channel, unread: (channel.unread += 1) });
     }
   }
@@ -267,6 +538,24 @@ export class IrcService {
     ircStore.channels.set(channelId, { ...channel, unread: 0 });
   }
 
+  // [{set: true, mode: "o", arg: "alice"}, {set: true, mode: "v", arg: "bob"}] → "+ov alice bob"
+  formatModeChanges(changes: ModeChange[]) {
+    let modes = "";
+    let sign: boolean | null = null;
+    const args: string[] = [];
+
+    for (const change of changes) {
+      if (change.set !== sign) {
+        modes += change.set ? "+" : "-";
+        sign = change.set;
+      }
+      modes += change.mode;
+      if (change.arg) args.push(change.arg);
+    }
+
+    return [modes, ...args].join(" ");
+  }
+
   addServerMessage(serverId: ServerId, message: ChatMessage) {
     const server = ircStore.servers.get(serverId);
     if (!server) return;
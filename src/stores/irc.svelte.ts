@@ -1,5 +1,6 @@
 import { SvelteMap } from "svelte/reactivity";
 import type { Channel, ChannelId, ChatMessage, Server, ServerId } from "../types/kirc.svelte";
+import type { ServerFeatures } from "../types/payloads.svelte";
 
 export class IrcStore {
   servers = $state(new SvelteMap<ServerId, Server>());
@@ -9,6 +10,9 @@ export class IrcStore {
   currentChannelId = $state<ChannelId | null>(null);
   nickErrors = $state(new SvelteMap<ServerId, string>());
   nickSuccess = $state(new SvelteMap<ServerId, string>());
+  serverFeatures = $state(new SvelteMap<ServerId, ServerFeatures>());
+  // 속도 제한으로 아직 보내지 못한 줄 수
+  sendQueue = $state(new SvelteMap<ServerId, number>());
 
   currentServer = $derived.by(() => {
     if (!this.currentServerId) return null;
@@ -1,4 +1,4 @@
-import type { SvelteSet } from "svelte/reactivity";
+import type { SvelteMap } from "svelte/reactivity";
 
 export type ServerId = string;
 export type ChannelId = string;
@@ -7,6 +7,7 @@ export type MessageId = string;
 export enum MessageType {
   USER,
   SYSTEM,
+  ACTION,
 }
 
 export type ChatMessage =
@@ -17,6 +18,13 @@ export type ChatMessage =
       content: string;
       timestamp: number;
     }
+  | {
+      type: MessageType.ACTION;
+      id: MessageId;
+      nickname: string;
+      content: string;
+      timestamp: number;
+    }
   | {
       type: MessageType.SYSTEM;
       id: MessageId;
@@ -28,9 +36,14 @@ export type Channel = {
   serverId: ServerId;
   name: string;
   topic?: string;
-  users: SvelteSet<string>;
+  topicSetBy?: string;
+  topicSetAt?: number;
+  // nick → prefix (높은 권한 먼저, 예: "@+")
+  users: SvelteMap<string, string>;
   unread: number;
   locked: boolean;
+  // 개인 대화(query)면 name이 상대 닉네임
+  isQuery: boolean;
   lastReadMessageId?: MessageId;
 };
 
@@ -46,4 +59,11 @@ export type Server = {
 };
 
 // TODO: enum으로 바꿀수 있지 않을까?
-export type IrcServerStatus = "connecting" | "connected" | "registering" | "disconnected" | "error";
+export type IrcServerStatus =
+  | "connecting"
+  | "connected"
+  | "registering"
+  | "disconnected"
+  | "authfailed"
+  | "reconnecting"
+  | "error";
@@ -7,8 +7,16 @@ export enum ServerStatus {
   Disconnected = "Disconnected",
   Disconnecting = "Disconnecting",
   Failed = "Failed",
+  AuthFailed = "AuthFailed",
 }
 
+// Reconnecting은 재시도 정보를 담은 객체로 전달됨
+export type ReconnectingStatus = {
+  Reconnecting: { attempt: number; next_retry_at: number };
+};
+
+export type ServerStatusValue = ServerStatus | ReconnectingStatus;
+
 export type ServerDetail = {
   serverId: string;
   host: string;
@@ -20,23 +28,71 @@ export type ServerDetail = {
 
 export type ServerStatusPayload = {
   serverId: string;
-  status: ServerStatus;
+  status: ServerStatusValue;
+};
+
+export type ModeChange = {
+  set: boolean;
+  mode: string;
+  arg: string | null;
 };
 
 export type UiEventPayload =
   | {
       type: "UserMessage";
       server_id: string;
-      channel: string;
+      channel: string | null;
       nick: string;
       content: string;
       timestamp: number;
     }
+  | {
+      type: "Action";
+      server_id: string;
+      channel: string | null;
+      nick: string;
+      content: string;
+      timestamp: number;
+    }
+  | {
+      type: "Notice";
+      server_id: string;
+      channel: string | null;
+      nick: string;
+      content: string;
+      timestamp: number;
+    }
+  | {
+      type: "CtcpReply";
+      server_id: string;
+      nick: string;
+      command: string;
+      params: string;
+      rtt_ms?: number;
+    }
   | { type: "Join"; server_id: string; channel: string; nick: string }
   | { type: "Part"; server_id: string; channel: string; nick: string; reason?: string }
+  | {
+      type: "Kick";
+      server_id: string;
+      channel: string;
+      nick: string;
+      by: string;
+      reason?: string;
+    }
+  | { type: "Invite"; server_id: string; channel: string; by: string }
   | { type: "Quit"; server_id: string; nick: string; reason?: string }
   | { type: "Nick"; server_id: string; old_nick: string; new_nick: string }
-  | { type: "Topic"; server_id: string; channel: string; topic?: string }
+  | {
+      type: "Topic";
+      server_id: string;
+      channel: string;
+      topic?: string;
+      set_by?: string;
+      set_at?: number;
+      on_join: boolean;
+    }
+  | { type: "Mode"; server_id: string; target: string; setter: string; changes: ModeChange[] }
   | { type: "Error"; server_id: string; message: string };
 
 export type ChannelLockChangedEvent = {
@@ -49,3 +105,64 @@ export type ChangeNickFailedPayload = {
   serverId: ServerId;
   reason: string;
 };
+
+export type TlsUntrustedPayload = {
+  serverId: ServerId;
+  endpoint: string;
+  fingerprint: string;
+};
+
+export type ServerFeatures = {
+  network: string | null;
+  chantypes: string;
+  prefix: { mode: string; prefix: string }[];
+  chanmodes: { list: string; alwaysParam: string; setParam: string; flag: string };
+  casemapping: string;
+  nicklen: number | null;
+  topiclen: number | null;
+  maxtargets: number | null;
+  targmax: Record<string, number | null>;
+  statusmsg: string;
+  monitor: boolean;
+  monitorLimit: number | null;
+  tokens: Record<string, string | null>;
+};
+
+export type ServerFeaturesPayload = {
+  serverId: ServerId;
+  name: string;
+  features: ServerFeatures;
+};
+
+export type ChannelUser = {
+  nick: string;
+  prefix: string;
+};
+
+export type MembersChange =
+  | { type: "Reset"; users: ChannelUser[] }
+  | { type: "Joined"; user: ChannelUser }
+  | { type: "Left"; nick: string }
+  | { type: "Renamed"; oldNick: string; user: ChannelUser }
+  | { type: "Updated"; user: ChannelUser };
+
+export type QueryChange =
+  | { type: "Opened"; nick: string }
+  | { type: "Closed"; nick: string }
+  | { type: "Renamed"; oldNick: string; nick: string };
+
+export type QueryChangedPayload = {
+  serverId: ServerId;
+  change: QueryChange;
+};
+
+export type SendQueuePayload = {
+  serverId: ServerId;
+  depth: number;
+};
+
+export type MembersChangedPayload = {
+  serverId: ServerId;
+  channel: string;
+  change: MembersChange;
+};